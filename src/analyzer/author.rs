@@ -0,0 +1,529 @@
+//! 開発者の識別を担当するモジュール
+//!
+//! このモジュールは、`.mailmap`や別名ファイルを用いて、
+//! 同一人物の異なる名前・メールアドレスを1人の開発者として扱うための機能と、
+//! ボットなど分析から除外する開発者を判定する機能を提供します。
+
+use super::error::AnalyzerError;
+use git2::{Mailmap, Signature, Time};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+
+/// デフォルトで分析から除外するボット・サービスアカウント
+///
+/// `/`で囲んだものは正規表現、それ以外は名前またはメールアドレスとの完全一致
+/// （大文字・小文字を区別しない）として扱います。
+pub const DEFAULT_BOT_AUTHORS: &[&str] = &[
+    // GitHub Appsのボット（dependabot[bot]、renovate[bot]、github-actions[bot]など）
+    r"/(?i)\[bot\]$/",
+    r"/(?i)\[bot\]@users\.noreply\.github\.com$/",
+    "dependabot",
+    "dependabot-preview",
+    "renovate",
+    "renovate-bot",
+    "bot@renovateapp.com",
+    "greenkeeper",
+    "greenkeeperio-bot",
+    "snyk-bot",
+    "pyup-bot",
+    "depfu",
+    "semantic-release-bot",
+    "github-actions",
+    "allcontributors",
+];
+
+/// 開発者を識別するキーの種類を表す列挙型
+///
+/// - `Name`: コミットの作成者名で識別する
+/// - `Email`: コミットの作成者メールアドレスで識別する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AuthorKey {
+    #[default]
+    Name,
+    Email,
+}
+
+impl FromStr for AuthorKey {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "email" => Ok(Self::Email),
+            _ => Err(AnalyzerError::InvalidOption(format!(
+                "Unknown author key: {} (expected name or email)",
+                s
+            ))),
+        }
+    }
+}
+
+/// 共同作成者（`Co-authored-by:`など）への貢献度の配分方法を表す列挙型
+///
+/// - `Full`: 共同作成者それぞれに作成者と同じ1コミット分の貢献を与える
+/// - `Fractional`: 1コミット分の貢献を作成者と共同作成者で等分する
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CoauthorCredit {
+    #[default]
+    Full,
+    Fractional,
+}
+
+impl FromStr for CoauthorCredit {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(Self::Full),
+            "fractional" => Ok(Self::Fractional),
+            _ => Err(AnalyzerError::InvalidOption(format!(
+                "Unknown co-author credit: {} (expected full or fractional)",
+                s
+            ))),
+        }
+    }
+}
+
+/// 開発者の別名と正規の名前の対応を保持する構造体
+///
+/// 別名は大文字・小文字を区別せず、名前またはメールアドレスと照合されます。
+///
+/// # ファイル形式
+///
+/// 1行に1人ずつ、`正規の名前 = 別名1, 別名2`の形式で記述します。
+/// `#`で始まる行と空行は無視されます。
+///
+/// ```text
+/// # 正規の名前 = 別名（名前またはメールアドレス）
+/// Taro Yamada = taro, yamada-t, taro@example.com
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AuthorAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl AuthorAliases {
+    /// 別名ファイルを読み込みます
+    ///
+    /// # エラー
+    ///
+    /// ファイルの読み込みに失敗した場合、または形式が不正な場合にエラーを返します
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AnalyzerError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AnalyzerError::InvalidOption(format!(
+                "Failed to read alias file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(&content)
+    }
+
+    /// 別名ファイルの内容を解析します
+    ///
+    /// # エラー
+    ///
+    /// `=`を含まない行がある場合、または正規の名前が空の場合にエラーを返します
+    pub fn parse(content: &str) -> Result<Self, AnalyzerError> {
+        let mut aliases = Self::default();
+
+        for (line_number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (canonical, names) = line
+                .split_once('=')
+                .map(|(canonical, names)| (canonical.trim(), names))
+                .filter(|(canonical, _)| !canonical.is_empty())
+                .ok_or_else(|| {
+                    AnalyzerError::InvalidOption(format!(
+                        "Invalid alias definition at line {}: {}",
+                        line_number + 1,
+                        line
+                    ))
+                })?;
+
+            for alias in names.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+                aliases.insert(canonical, alias);
+            }
+        }
+
+        Ok(aliases)
+    }
+
+    /// 別名を追加します
+    pub fn insert(&mut self, canonical: &str, alias: &str) {
+        self.aliases
+            .insert(alias.to_lowercase(), canonical.to_string());
+    }
+
+    /// 別の定義の別名を追加します。同じ別名は`other`の定義を優先します
+    pub fn merge(&mut self, other: AuthorAliases) {
+        self.aliases.extend(other.aliases);
+    }
+
+    /// 別名が登録されていない場合に`true`を返します
+    pub fn is_empty(&self) -> bool {
+        self.aliases.is_empty()
+    }
+
+    /// 名前またはメールアドレスに対応する正規の名前を返します
+    fn resolve(&self, name: &str, email: &str) -> Option<&str> {
+        self.aliases
+            .get(&name.to_lowercase())
+            .or_else(|| self.aliases.get(&email.to_lowercase()))
+            .map(|canonical| canonical.as_str())
+    }
+}
+
+/// 開発者の名前やメールアドレスと照合するパターン
+///
+/// - `Exact`: 大文字・小文字を区別しない完全一致
+/// - `Regex`: 正規表現（`/`で囲んで指定）
+#[derive(Debug, Clone)]
+enum AuthorPattern {
+    Exact(String),
+    Regex(Regex),
+}
+
+impl AuthorPattern {
+    /// パターンの文字列を解析します
+    fn parse(pattern: &str) -> Result<Self, AnalyzerError> {
+        match pattern
+            .strip_prefix('/')
+            .and_then(|rest| rest.strip_suffix('/'))
+        {
+            Some(regex) => Regex::new(regex)
+                .map(Self::Regex)
+                .map_err(|e| AnalyzerError::InvalidPattern(e.to_string())),
+            None => Ok(Self::Exact(pattern.to_lowercase())),
+        }
+    }
+
+    fn is_match(&self, identity: &str) -> bool {
+        match self {
+            Self::Exact(expected) => identity.to_lowercase() == *expected,
+            Self::Regex(regex) => regex.is_match(identity),
+        }
+    }
+}
+
+/// 分析に含める開発者と除外する開発者を判定する構造体
+///
+/// 開発者は名前、メールアドレス、名寄せ後の名前のいずれかがパターンに一致するかで判定します。
+/// 除外パターンはインクルードパターンより優先されます。
+///
+/// # フィールド
+///
+/// - `include`: 分析に含める開発者のパターン。空の場合は全ての開発者を含める
+/// - `exclude`: 分析から除外する開発者のパターン
+#[derive(Debug, Clone, Default)]
+pub(crate) struct AuthorFilter {
+    include: Vec<AuthorPattern>,
+    exclude: Vec<AuthorPattern>,
+}
+
+impl AuthorFilter {
+    /// 新しいAuthorFilterインスタンスを作成します
+    ///
+    /// # 引数
+    ///
+    /// - `include`: 分析に含める開発者のパターン
+    /// - `exclude`: 分析から除外する開発者のパターン
+    /// - `exclude_bots`: `DEFAULT_BOT_AUTHORS`のボットを除外するかどうか
+    ///
+    /// # エラー
+    ///
+    /// 正規表現のパターンが不正な場合にエラーを返します
+    pub(crate) fn new(
+        include: &[String],
+        exclude: &[String],
+        exclude_bots: bool,
+    ) -> Result<Self, AnalyzerError> {
+        let bots = DEFAULT_BOT_AUTHORS
+            .iter()
+            .filter(|_| exclude_bots)
+            .map(|pattern| pattern.to_string());
+
+        Ok(Self {
+            include: include
+                .iter()
+                .map(|pattern| AuthorPattern::parse(pattern))
+                .collect::<Result<_, _>>()?,
+            exclude: exclude
+                .iter()
+                .cloned()
+                .chain(bots)
+                .map(|pattern| AuthorPattern::parse(&pattern))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// 名前・メールアドレスなどの識別子を持つ開発者を分析から除外するかどうかを判定します
+    pub(crate) fn is_excluded(&self, identities: &[&str]) -> bool {
+        let matches = |patterns: &[AuthorPattern]| {
+            patterns.iter().any(|pattern| {
+                identities
+                    .iter()
+                    .filter(|identity| !identity.is_empty())
+                    .any(|identity| pattern.is_match(identity))
+            })
+        };
+
+        matches(&self.exclude) || (!self.include.is_empty() && !matches(&self.include))
+    }
+}
+
+/// コミットの作成者を分析で使用する開発者名に解決する構造体
+///
+/// 解決は以下の順序で行われます：
+///
+/// 1. `.mailmap`による名前・メールアドレスの正規化
+/// 2. 別名ファイルによる正規の名前への置き換え
+/// 3. `AuthorKey`に従った名前またはメールアドレスの選択
+pub(crate) struct AuthorResolver {
+    mailmap: Option<Mailmap>,
+    key: AuthorKey,
+    aliases: AuthorAliases,
+}
+
+impl AuthorResolver {
+    /// 新しいAuthorResolverインスタンスを作成します
+    ///
+    /// # 引数
+    ///
+    /// - `mailmap`: 使用する`.mailmap`。`None`の場合は正規化を行わない
+    /// - `key`: 開発者を識別するキーの種類
+    /// - `aliases`: 開発者の別名
+    pub(crate) fn new(mailmap: Option<Mailmap>, key: AuthorKey, aliases: AuthorAliases) -> Self {
+        Self {
+            mailmap,
+            key,
+            aliases,
+        }
+    }
+
+    /// 名前とメールアドレスから開発者名を解決します
+    pub(crate) fn resolve(&self, name: &str, email: &str) -> String {
+        let (name, email) = self.apply_mailmap(name, email);
+
+        if let Some(canonical) = self.aliases.resolve(&name, &email) {
+            return canonical.to_string();
+        }
+
+        match self.key {
+            AuthorKey::Email if !email.is_empty() => email.to_lowercase(),
+            _ if name.is_empty() => email.to_lowercase(),
+            _ => name,
+        }
+    }
+
+    /// `.mailmap`を適用した名前とメールアドレスを返します
+    fn apply_mailmap(&self, name: &str, email: &str) -> (String, String) {
+        let resolved = self.mailmap.as_ref().and_then(|mailmap| {
+            let signature = Signature::new(name, email, &Time::new(0, 0)).ok()?;
+            mailmap.resolve_signature(&signature).ok()
+        });
+
+        match resolved {
+            Some(signature) => (
+                signature.name().unwrap_or(name).to_string(),
+                signature.email().unwrap_or(email).to_string(),
+            ),
+            None => (name.to_string(), email.to_string()),
+        }
+    }
+}
+
+/// コミットメッセージから指定されたトレーラーの名前とメールアドレスを抽出します
+///
+/// `git interpret-trailers`と同様に、最後の空行より後の段落（トレーラーブロック）だけを読み込みます。
+/// 件名だけのメッセージや、本文の途中に書かれた同じ形式の行はトレーラーとして扱いません。
+/// トレーラーのキーは大文字・小文字を区別せずに照合します。
+/// `Co-authored-by: Name <email>`の形式のほか、メールアドレスのない`Co-authored-by: Name`も受け付けます。
+pub(crate) fn parse_trailers(message: &str, keys: &[String]) -> Vec<(String, String)> {
+    let mut identities = Vec::new();
+
+    let lines: Vec<&str> = message.trim_end().lines().collect();
+    let Some(blank) = lines.iter().rposition(|line| line.trim().is_empty()) else {
+        return identities;
+    };
+
+    for line in &lines[blank + 1..] {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        if !keys.iter().any(|k| k.eq_ignore_ascii_case(key.trim())) {
+            continue;
+        }
+
+        let value = value.trim();
+        let (name, email) = match (value.find('<'), value.rfind('>')) {
+            (Some(start), Some(end)) if start < end => {
+                (value[..start].trim(), value[start + 1..end].trim())
+            }
+            _ => (value, ""),
+        };
+
+        if !name.is_empty() || !email.is_empty() {
+            identities.push((name.to_string(), email.to_string()));
+        }
+    }
+
+    identities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_author_key_from_str() {
+        assert_eq!("name".parse::<AuthorKey>().unwrap(), AuthorKey::Name);
+        assert_eq!("email".parse::<AuthorKey>().unwrap(), AuthorKey::Email);
+        assert!("login".parse::<AuthorKey>().is_err());
+    }
+
+    #[test]
+    fn test_coauthor_credit_from_str() {
+        assert_eq!(
+            "full".parse::<CoauthorCredit>().unwrap(),
+            CoauthorCredit::Full
+        );
+        assert_eq!(
+            "fractional".parse::<CoauthorCredit>().unwrap(),
+            CoauthorCredit::Fractional
+        );
+        assert!("half".parse::<CoauthorCredit>().is_err());
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        let message = "Add feature\n\nSome description.\n\nco-authored-by: Hanako <hanako@example.com>\nCo-Authored-By: Jiro\nPaired-with: Saburo <saburo@example.com>\nSigned-off-by: Taro <taro@example.com>\n";
+
+        let keys = vec!["Co-authored-by".to_string()];
+        assert_eq!(
+            parse_trailers(message, &keys),
+            vec![
+                ("Hanako".to_string(), "hanako@example.com".to_string()),
+                ("Jiro".to_string(), "".to_string()),
+            ]
+        );
+
+        let keys = vec!["Paired-with".to_string()];
+        assert_eq!(
+            parse_trailers(message, &keys),
+            vec![("Saburo".to_string(), "saburo@example.com".to_string())]
+        );
+
+        assert!(parse_trailers(message, &[]).is_empty());
+
+        // 最後の段落以外の行と、件名はトレーラーとして扱わない
+        let keys = vec!["Co-authored-by".to_string()];
+        let message = "Add feature\n\nCo-authored-by: Hanako <hanako@example.com>\nwas reverted.\n\nSigned-off-by: Taro <taro@example.com>\nCo-authored-by: Jiro\n\n";
+        assert_eq!(
+            parse_trailers(message, &keys),
+            vec![("Jiro".to_string(), "".to_string())]
+        );
+        assert!(parse_trailers("Co-authored-by: Jiro", &keys).is_empty());
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        let aliases = AuthorAliases::parse(
+            "# comment\n\nTaro Yamada = taro, yamada-t, Taro@Example.com\nHanako = hanako-s\n",
+        )
+        .unwrap();
+
+        assert_eq!(aliases.resolve("taro", ""), Some("Taro Yamada"));
+        assert_eq!(aliases.resolve("YAMADA-T", ""), Some("Taro Yamada"));
+        assert_eq!(
+            aliases.resolve("someone", "taro@example.com"),
+            Some("Taro Yamada")
+        );
+        assert_eq!(aliases.resolve("hanako-s", ""), Some("Hanako"));
+        assert_eq!(aliases.resolve("jiro", "jiro@example.com"), None);
+    }
+
+    #[test]
+    fn test_parse_aliases_invalid() {
+        assert!(AuthorAliases::parse("Taro Yamada taro").is_err());
+        assert!(AuthorAliases::parse(" = taro").is_err());
+    }
+
+    #[test]
+    fn test_resolver_with_mailmap() {
+        let mailmap =
+            Mailmap::from_buffer("Taro Yamada <taro@example.com> <yamada@old.example.com>\n")
+                .unwrap();
+        let resolver =
+            AuthorResolver::new(Some(mailmap), AuthorKey::Name, AuthorAliases::default());
+
+        assert_eq!(
+            resolver.resolve("yamada-t", "yamada@old.example.com"),
+            "Taro Yamada"
+        );
+        assert_eq!(resolver.resolve("jiro", "jiro@example.com"), "jiro");
+    }
+
+    #[test]
+    fn test_resolver_by_email() {
+        let resolver = AuthorResolver::new(None, AuthorKey::Email, AuthorAliases::default());
+
+        assert_eq!(
+            resolver.resolve("Taro", "Taro@Example.com"),
+            "taro@example.com"
+        );
+        // メールアドレスが空の場合は名前を使用
+        assert_eq!(resolver.resolve("taro", ""), "taro");
+    }
+
+    #[test]
+    fn test_author_filter_excludes_bots_by_default() {
+        let filter = AuthorFilter::new(&[], &[], true).unwrap();
+
+        assert!(filter.is_excluded(&[
+            "dependabot[bot]",
+            "49699333+dependabot[bot]@users.noreply.github.com"
+        ]));
+        assert!(filter.is_excluded(&["Renovate Bot", "bot@renovateapp.com"]));
+        assert!(filter.is_excluded(&["Snyk-Bot", ""]));
+        assert!(!filter.is_excluded(&["Taro", "taro@example.com"]));
+        assert!(!filter.is_excluded(&["Abbot", "abbot@example.com"]));
+
+        let filter = AuthorFilter::new(&[], &[], false).unwrap();
+        assert!(!filter.is_excluded(&["dependabot[bot]", ""]));
+    }
+
+    #[test]
+    fn test_author_filter_patterns() {
+        let filter = AuthorFilter::new(
+            &["/@example\\.com$/".to_string()],
+            &["release-bot@example.com".to_string(), "/^ci-/".to_string()],
+            false,
+        )
+        .unwrap();
+
+        assert!(!filter.is_excluded(&["Taro", "taro@example.com"]));
+        // インクルードパターンに一致しない開発者は除外
+        assert!(filter.is_excluded(&["Jiro", "jiro@example.org"]));
+        // 除外パターンはインクルードパターンより優先
+        assert!(filter.is_excluded(&["Release Bot", "Release-Bot@example.com"]));
+        assert!(filter.is_excluded(&["ci-runner", "runner@example.com"]));
+
+        assert!(AuthorFilter::new(&["/(/".to_string()], &[], false).is_err());
+    }
+
+    #[test]
+    fn test_resolver_aliases_take_precedence() {
+        let aliases = AuthorAliases::parse("Taro Yamada = taro@example.com").unwrap();
+        let resolver = AuthorResolver::new(None, AuthorKey::Email, aliases);
+
+        assert_eq!(resolver.resolve("taro", "taro@example.com"), "Taro Yamada");
+    }
+}
@@ -0,0 +1,307 @@
+//! コミットごとの分析結果をディスクにキャッシュするモジュール
+//!
+//! このモジュールは、差分の計算結果をコミットのOIDをキーとして保存し、
+//! 2回目以降の分析で新しいコミットの差分だけを計算できるようにする機能を提供します。
+//!
+//! キャッシュは差分の計算条件（パターンやリネーム検出のしきい値など）ごとに
+//! 別のファイルへ保存されるため、条件を変更すると自動的に別のキャッシュが使用されます。
+//! キャッシュが増え続けないよう、保存時には他の条件のキャッシュファイルを削除します。
+
+use super::error::AnalyzerError;
+use super::git::ExtractedCommit;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+/// キャッシュファイルの形式のバージョン
+///
+/// 保存する内容の構造やパターンの解釈を変更した場合は値を増やし、古いキャッシュを無効化します。
+const CACHE_FORMAT_VERSION: u32 = 3;
+
+/// キャッシュファイルの先頭行に保存するヘッダー
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+struct CacheHeader {
+    version: u32,
+    fingerprint: String,
+}
+
+/// キャッシュファイルの1行分のエントリー
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    oid: String,
+    commit: ExtractedCommit,
+}
+
+/// コミットごとの差分の計算結果を保持するキャッシュ
+///
+/// キャッシュファイルはJSON Lines形式で、1行目にヘッダー、2行目以降に
+/// コミットごとのエントリーを保存します。新しいエントリーは末尾に追記されます。
+///
+/// # フィールド
+///
+/// - `path`: キャッシュファイルのパス
+/// - `entries`: 読み込んだエントリー（OIDをキーとする）
+/// - `pending`: まだ保存していない新しいエントリー
+pub(crate) struct CommitCache {
+    path: PathBuf,
+    entries: HashMap<String, ExtractedCommit>,
+    pending: Vec<CacheEntry>,
+}
+
+impl CommitCache {
+    /// キャッシュディレクトリから、指定された計算条件に対応するキャッシュを読み込みます
+    ///
+    /// キャッシュファイルが存在しない場合や、形式のバージョンが異なる場合は空のキャッシュを返します。
+    /// 壊れた行は読み飛ばします。
+    ///
+    /// # エラー
+    ///
+    /// キャッシュディレクトリの作成やファイルの読み込みに失敗した場合にエラーを返します
+    pub(crate) fn load(dir: &Path, fingerprint: &str) -> Result<Self, AnalyzerError> {
+        fs::create_dir_all(dir).map_err(|e| cache_error(dir, e))?;
+        let path = dir.join(format!("commits-{}.jsonl", fingerprint));
+
+        let mut cache = Self {
+            path,
+            entries: HashMap::new(),
+            pending: Vec::new(),
+        };
+
+        let file = match File::open(&cache.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(cache),
+            Err(e) => return Err(cache_error(&cache.path, e)),
+        };
+
+        let expected_header = CacheHeader {
+            version: CACHE_FORMAT_VERSION,
+            fingerprint: fingerprint.to_string(),
+        };
+        let mut lines = BufReader::new(file).lines();
+        let header = lines
+            .next()
+            .and_then(|line| line.ok())
+            .and_then(|line| serde_json::from_str::<CacheHeader>(&line).ok());
+        if header.as_ref() != Some(&expected_header) {
+            // 形式が異なるキャッシュは保存時に作り直す
+            return Ok(cache);
+        }
+
+        for line in lines {
+            let line = line.map_err(|e| cache_error(&cache.path, e))?;
+            if let Ok(entry) = serde_json::from_str::<CacheEntry>(&line) {
+                cache.entries.insert(entry.oid, entry.commit);
+            }
+        }
+
+        Ok(cache)
+    }
+
+    /// OIDに対応するキャッシュ済みの計算結果を返します
+    pub(crate) fn get(&self, oid: &str) -> Option<&ExtractedCommit> {
+        self.entries.get(oid)
+    }
+
+    /// 新しい計算結果を追加します（`save`を呼ぶまでファイルには書き込まれません）
+    pub(crate) fn insert(&mut self, oid: String, commit: ExtractedCommit) {
+        self.pending.push(CacheEntry { oid, commit });
+    }
+
+    /// 追加された計算結果をキャッシュファイルに保存し、他の計算条件のキャッシュファイルを削除します
+    ///
+    /// 既存のキャッシュが読み込めなかった場合は、ヘッダーからファイルを作り直します。
+    /// 前回の書き込みが中断されてファイルが改行で終わっていない場合は、
+    /// 新しいエントリーが途中の行とつながらないよう、先に改行を書き込みます。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの書き込みや削除に失敗した場合にエラーを返します
+    pub(crate) fn save(&mut self, fingerprint: &str) -> Result<(), AnalyzerError> {
+        self.remove_other_caches()?;
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let rewrite = self.entries.is_empty();
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .write(true)
+            .append(!rewrite)
+            .truncate(rewrite)
+            .open(&self.path)
+            .map_err(|e| cache_error(&self.path, e))?;
+        let needs_newline =
+            !rewrite && !ends_with_newline(&mut file).map_err(|e| cache_error(&self.path, e))?;
+        let mut writer = BufWriter::new(file);
+        if needs_newline {
+            writeln!(writer).map_err(|e| cache_error(&self.path, e))?;
+        }
+
+        if rewrite {
+            let header = CacheHeader {
+                version: CACHE_FORMAT_VERSION,
+                fingerprint: fingerprint.to_string(),
+            };
+            write_json_line(&mut writer, &header, &self.path)?;
+        }
+        for entry in &self.pending {
+            write_json_line(&mut writer, entry, &self.path)?;
+        }
+        writer.flush().map_err(|e| cache_error(&self.path, e))?;
+
+        for entry in self.pending.drain(..) {
+            self.entries.insert(entry.oid, entry.commit);
+        }
+        Ok(())
+    }
+
+    /// キャッシュディレクトリから、このキャッシュ以外のキャッシュファイルを削除します
+    fn remove_other_caches(&self) -> Result<(), AnalyzerError> {
+        let Some(dir) = self.path.parent() else {
+            return Ok(());
+        };
+        for entry in fs::read_dir(dir).map_err(|e| cache_error(dir, e))? {
+            let path = entry.map_err(|e| cache_error(dir, e))?.path();
+            let is_cache = path.is_file()
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with("commits-") && name.ends_with(".jsonl"));
+            if is_cache && path != self.path {
+                fs::remove_file(&path).map_err(|e| cache_error(&path, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// ファイルが空か、改行で終わっているかどうかを返します
+fn ends_with_newline(file: &mut File) -> std::io::Result<bool> {
+    if file.seek(SeekFrom::End(0))? == 0 {
+        return Ok(true);
+    }
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last)?;
+    Ok(last[0] == b'\n')
+}
+
+/// 値をJSONに変換し、キャッシュファイルに1行として書き込みます
+fn write_json_line(
+    writer: &mut impl Write,
+    value: &impl Serialize,
+    path: &Path,
+) -> Result<(), AnalyzerError> {
+    let line =
+        serde_json::to_string(value).map_err(|e| AnalyzerError::CacheError(e.to_string()))?;
+    writeln!(writer, "{}", line).map_err(|e| cache_error(path, e))
+}
+
+/// 入出力エラーをキャッシュのエラーに変換します
+fn cache_error(path: &Path, error: std::io::Error) -> AnalyzerError {
+    AnalyzerError::CacheError(format!("{}: {}", path.display(), error))
+}
+
+/// 文字列から安定したフィンガープリントを計算します（FNV-1a 64bit）
+///
+/// 標準ライブラリのハッシュ関数はRustのバージョンによって結果が変わる可能性があるため、
+/// キャッシュファイル名には使用しません。
+pub(crate) fn fingerprint(input: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in input.as_bytes() {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const ENTRY: &str = r#"{"oid":"abc","commit":{"author_name":"taro","author_email":"taro@example.com","message":"Add","timestamp":0,"files":[{"path":"a.rs","old_path":null,"deleted":false,"lines_added":1,"lines_deleted":0}]}}"#;
+
+    fn write_cache(dir: &Path, fingerprint: &str, lines: &[&str]) {
+        let content: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        fs::write(dir.join(format!("commits-{}.jsonl", fingerprint)), content).unwrap();
+    }
+
+    #[test]
+    fn test_fingerprint_is_stable() {
+        assert_eq!(fingerprint(""), "cbf29ce484222325");
+        assert_eq!(fingerprint("a"), "af63dc4c8601ec8c");
+        assert_ne!(fingerprint("include=[]"), fingerprint("include=[\"a\"]"));
+    }
+
+    #[test]
+    fn test_load_skips_broken_lines() {
+        let temp_dir = TempDir::new().unwrap();
+        let header = format!(
+            r#"{{"version":{},"fingerprint":"f"}}"#,
+            CACHE_FORMAT_VERSION
+        );
+        write_cache(temp_dir.path(), "f", &[&header, "{broken", ENTRY]);
+
+        let cache = CommitCache::load(temp_dir.path(), "f").unwrap();
+        let expected = serde_json::from_str::<CacheEntry>(ENTRY).unwrap().commit;
+        assert_eq!(cache.get("abc"), Some(&expected));
+        assert!(cache.get("def").is_none());
+    }
+
+    #[test]
+    fn test_save_after_interrupted_write() {
+        let temp_dir = TempDir::new().unwrap();
+        let header = format!(
+            r#"{{"version":{},"fingerprint":"f"}}"#,
+            CACHE_FORMAT_VERSION
+        );
+        // 前回の書き込みが途中で中断され、最後の行が改行で終わっていない
+        let truncated = &ENTRY[..ENTRY.len() / 2];
+        fs::write(
+            temp_dir.path().join("commits-f.jsonl"),
+            format!("{}\n{}\n{}", header, ENTRY, truncated),
+        )
+        .unwrap();
+        write_cache(temp_dir.path(), "old", &[&header, ENTRY]);
+
+        let mut cache = CommitCache::load(temp_dir.path(), "f").unwrap();
+        let commit = serde_json::from_str::<CacheEntry>(ENTRY).unwrap().commit;
+        cache.insert("def".to_string(), commit);
+        cache.save("f").unwrap();
+
+        // 新しいエントリーは途中の行とつながらずに読み込める
+        let cache = CommitCache::load(temp_dir.path(), "f").unwrap();
+        assert!(cache.get("abc").is_some());
+        assert!(cache.get("def").is_some());
+
+        // 他の計算条件のキャッシュファイルは削除される
+        assert!(!temp_dir.path().join("commits-old.jsonl").exists());
+        assert!(temp_dir.path().join("commits-f.jsonl").exists());
+    }
+
+    #[test]
+    fn test_load_ignores_other_format_versions() {
+        let temp_dir = TempDir::new().unwrap();
+        let header = format!(
+            r#"{{"version":{},"fingerprint":"f"}}"#,
+            CACHE_FORMAT_VERSION + 1
+        );
+        write_cache(temp_dir.path(), "f", &[&header, ENTRY]);
+
+        let mut cache = CommitCache::load(temp_dir.path(), "f").unwrap();
+        assert!(cache.get("abc").is_none());
+
+        // 保存時に現在の形式で作り直される
+        let commit = serde_json::from_str::<CacheEntry>(ENTRY).unwrap().commit;
+        cache.insert("def".to_string(), commit);
+        cache.save("f").unwrap();
+
+        let cache = CommitCache::load(temp_dir.path(), "f").unwrap();
+        assert!(cache.get("abc").is_none());
+        assert!(cache.get("def").is_some());
+    }
+}
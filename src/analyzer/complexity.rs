@@ -0,0 +1,262 @@
+//! ファイルの現在の規模と複雑さを計算するモジュール
+//!
+//! このモジュールは、分析対象のリビジョンにおけるファイルの内容から、
+//! 行数とインデントに基づく複雑さを計算する機能を提供します。
+//! インデントに基づく複雑さは言語によらず計算でき、深くネストしたコードほど大きくなります。
+//!
+//! `tree-sitter`フィーチャーを有効にしてビルドした場合は、対応する言語のファイルについて
+//! 構文木から循環的複雑度も計算します。
+
+use std::collections::HashMap;
+
+/// インデントの幅を判定できない場合に1段とみなす幅（空白の数）
+const DEFAULT_INDENT_WIDTH: usize = 4;
+
+/// タブ1文字に相当する空白の数
+const TAB_WIDTH: usize = 4;
+
+/// ファイルの規模と複雑さを保持する構造体
+///
+/// # フィールド
+///
+/// - `lines_of_code`: 空行を除いた行数
+/// - `indentation_complexity`: 空行を除いた各行のインデントの段数の合計
+/// - `cyclomatic_complexity`: 構文木から求めた循環的複雑度。対応していない言語や、`tree-sitter`フィーチャーが無効な場合は`None`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CodeComplexity {
+    pub lines_of_code: u32,
+    pub indentation_complexity: f64,
+    pub cyclomatic_complexity: Option<u32>,
+}
+
+impl CodeComplexity {
+    /// ファイルの内容から規模と複雑さを計算します
+    ///
+    /// 循環的複雑度は、パスの拡張子から判定した言語の構文木から計算します。
+    pub fn measure(path: &str, content: &str) -> Self {
+        let (lines_of_code, indentation_complexity) = indentation_complexity(content);
+        Self {
+            lines_of_code,
+            indentation_complexity,
+            cyclomatic_complexity: cyclomatic_complexity(path, content),
+        }
+    }
+}
+
+/// 空行を除いた行数と、インデントに基づく複雑さを計算します
+///
+/// 各行のインデントの幅（タブは`TAB_WIDTH`個の空白）を1段の幅で割った値を段数とし、
+/// その合計を複雑さとします。1段の幅は、前の行からインデントが深くなった幅のうち
+/// 最も多く現れるもの（同数の場合は小さいもの）とし、2空白と4空白のどちらで
+/// インデントしたコードでも同じ値になるようにします。
+pub fn indentation_complexity(content: &str) -> (u32, f64) {
+    let widths: Vec<usize> = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.chars()
+                .take_while(|c| c.is_whitespace())
+                .map(|c| if c == '\t' { TAB_WIDTH } else { 1 })
+                .sum()
+        })
+        .collect();
+
+    let mut increases: HashMap<usize, usize> = HashMap::new();
+    for pair in widths.windows(2) {
+        if pair[1] > pair[0] {
+            *increases.entry(pair[1] - pair[0]).or_insert(0) += 1;
+        }
+    }
+    let indent_width = increases
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(width, _)| width)
+        .unwrap_or(DEFAULT_INDENT_WIDTH);
+
+    let complexity = widths
+        .iter()
+        .map(|&width| width as f64 / indent_width as f64)
+        .sum();
+    (widths.len() as u32, complexity)
+}
+
+/// 対応する言語のファイルの循環的複雑度を計算します
+///
+/// 分岐（`if`、ループ、`case`、`catch`、三項演算子、`&&`・`||`など）の数に1を加えた値を返します。
+/// 対応していない言語や、構文解析に失敗した場合は`None`を返します。
+#[cfg(feature = "tree-sitter")]
+pub fn cyclomatic_complexity(path: &str, content: &str) -> Option<u32> {
+    let (language, decision_kinds): (tree_sitter::Language, &[&str]) =
+        match path.rsplit_once('.')?.1.to_lowercase().as_str() {
+            "rs" => (
+                tree_sitter_rust::LANGUAGE.into(),
+                &[
+                    "if_expression",
+                    "while_expression",
+                    "for_expression",
+                    "match_arm",
+                ],
+            ),
+            "py" | "pyi" => (
+                tree_sitter_python::LANGUAGE.into(),
+                &[
+                    "if_statement",
+                    "elif_clause",
+                    "for_statement",
+                    "while_statement",
+                    "except_clause",
+                    "conditional_expression",
+                    "boolean_operator",
+                    "case_clause",
+                    "for_in_clause",
+                    "if_clause",
+                ],
+            ),
+            "js" | "jsx" | "mjs" | "cjs" => (tree_sitter_javascript::LANGUAGE.into(), JS_DECISIONS),
+            "ts" | "mts" | "cts" => (
+                tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+                JS_DECISIONS,
+            ),
+            "tsx" => (tree_sitter_typescript::LANGUAGE_TSX.into(), JS_DECISIONS),
+            "go" => (
+                tree_sitter_go::LANGUAGE.into(),
+                &[
+                    "if_statement",
+                    "for_statement",
+                    "expression_case",
+                    "type_case",
+                    "communication_case",
+                ],
+            ),
+            "java" => (
+                tree_sitter_java::LANGUAGE.into(),
+                &[
+                    "if_statement",
+                    "for_statement",
+                    "enhanced_for_statement",
+                    "while_statement",
+                    "do_statement",
+                    "switch_label",
+                    "catch_clause",
+                    "ternary_expression",
+                ],
+            ),
+            "c" | "h" => (tree_sitter_c::LANGUAGE.into(), C_DECISIONS),
+            "cc" | "cpp" | "cxx" | "hh" | "hpp" | "hxx" => (
+                tree_sitter_cpp::LANGUAGE.into(),
+                &[
+                    "if_statement",
+                    "for_statement",
+                    "for_range_loop",
+                    "while_statement",
+                    "do_statement",
+                    "case_statement",
+                    "catch_clause",
+                    "conditional_expression",
+                ],
+            ),
+            _ => return None,
+        };
+
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
+
+    let mut decisions = 0;
+    let mut cursor = tree.walk();
+    loop {
+        let node = cursor.node();
+        let is_logical_operator = node.kind() == "binary_expression"
+            && node
+                .child_by_field_name("operator")
+                .is_some_and(|operator| matches!(operator.kind(), "&&" | "||" | "??"));
+        if decision_kinds.contains(&node.kind()) || is_logical_operator {
+            decisions += 1;
+        }
+
+        if cursor.goto_first_child() || cursor.goto_next_sibling() {
+            continue;
+        }
+        loop {
+            if !cursor.goto_parent() {
+                return Some(decisions + 1);
+            }
+            if cursor.goto_next_sibling() {
+                break;
+            }
+        }
+    }
+}
+
+/// `tree-sitter`フィーチャーが無効な場合は、循環的複雑度を計算しません
+#[cfg(not(feature = "tree-sitter"))]
+pub fn cyclomatic_complexity(_path: &str, _content: &str) -> Option<u32> {
+    None
+}
+
+/// JavaScriptとTypeScriptで分岐として数える構文木のノード
+#[cfg(feature = "tree-sitter")]
+const JS_DECISIONS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "for_in_statement",
+    "while_statement",
+    "do_statement",
+    "switch_case",
+    "catch_clause",
+    "ternary_expression",
+];
+
+/// Cで分岐として数える構文木のノード
+#[cfg(feature = "tree-sitter")]
+const C_DECISIONS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "while_statement",
+    "do_statement",
+    "case_statement",
+    "conditional_expression",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_indentation_complexity() {
+        let four_spaces = "fn main() {\n    if a {\n        b();\n    }\n\n}\n";
+        let (lines, complexity) = indentation_complexity(four_spaces);
+        assert_eq!(lines, 5);
+        assert!((complexity - 4.0).abs() < 1e-10);
+
+        // インデントの幅やタブによらず同じ段数になる
+        let two_spaces = "fn main() {\n  if a {\n    b();\n  }\n\n}\n";
+        assert_eq!(indentation_complexity(two_spaces), (5, complexity));
+        let tabs = "fn main() {\n\tif a {\n\t\tb();\n\t}\n\n}\n";
+        assert_eq!(indentation_complexity(tabs), (5, complexity));
+
+        assert_eq!(indentation_complexity(""), (0, 0.0));
+        assert_eq!(indentation_complexity("a\nb\n"), (2, 0.0));
+    }
+
+    #[test]
+    #[cfg(feature = "tree-sitter")]
+    fn test_cyclomatic_complexity() {
+        let rust = "fn f(a: bool, b: bool) -> u8 {\n    if a && b {\n        1\n    } else {\n        match a {\n            true => 2,\n            false => 3,\n        }\n    }\n}\n";
+        assert_eq!(cyclomatic_complexity("src/f.rs", rust), Some(5));
+
+        let python = "def f(a):\n    for x in a:\n        if x or not x:\n            pass\n";
+        assert_eq!(cyclomatic_complexity("f.py", python), Some(4));
+
+        let typescript = "function f(a: number) { return a > 0 ? a : -a; }\n";
+        assert_eq!(cyclomatic_complexity("f.ts", typescript), Some(2));
+
+        assert_eq!(cyclomatic_complexity("notes.txt", "if a"), None);
+    }
+
+    #[test]
+    #[cfg(not(feature = "tree-sitter"))]
+    fn test_cyclomatic_complexity_disabled() {
+        assert_eq!(cyclomatic_complexity("src/f.rs", "fn f() {}"), None);
+    }
+}
@@ -0,0 +1,343 @@
+//! プロジェクトの設定ファイルを扱うモジュール
+//!
+//! このモジュールは、リポジトリのルートに置かれた`hotspot.toml`から
+//! パターンや分析期間、スコアの重み、開発者の別名、出力形式などの設定を読み込む機能を提供します。
+//!
+//! `[profile.<名前>]`のテーブルには名前付きのプロファイルを定義でき、
+//! プロファイルを選択するとその値がファイル全体の設定を上書きします。
+//!
+//! ```toml
+//! include = ["src/**/*.rs"]
+//! time_window = 180
+//!
+//! [score]
+//! churn = 0.5
+//!
+//! [authors]
+//! "Alice Smith" = ["alice", "alice@example.com"]
+//!
+//! [profile.backend]
+//! include = ["server/**/*.go"]
+//! format = "csv"
+//! ```
+
+use super::author::AuthorAliases;
+use super::error::AnalyzerError;
+use super::group::Component;
+use super::options::ScoreWeights;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// リポジトリのルートで探す設定ファイルの名前
+pub const CONFIG_FILE_NAME: &str = "hotspot.toml";
+
+/// 設定ファイルの内容を保持する構造体
+///
+/// 指定されていない項目は`None`（または空）となり、コマンドラインのオプションや
+/// デフォルト値が使用されます。
+///
+/// # フィールド
+///
+/// - `preset`: デフォルトのインクルードパターンの代わりに使用する言語のプリセット
+/// - `auto_detect`: リポジトリで使用されている言語を検出し、インクルードパターンを選択するかどうか
+/// - `include`: 分析対象とするファイルパターン
+/// - `exclude`: 分析から除外するファイルパターン
+/// - `no_default_includes`: デフォルトのインクルードパターンを使用しないかどうか
+/// - `no_default_excludes`: デフォルトの除外パターンを使用しないかどうか
+/// - `time_window`: 分析対象期間（日数）
+/// - `format`: 出力形式
+/// - `top`: 出力するホットスポットの数
+/// - `score`: ホットスポットスコアに追加で反映する指標の重み
+/// - `authors`: 正規の名前と、その別名（名前またはメールアドレス）のリスト
+/// - `group_by`: メトリクスをまとめて集計する単位（`dir:N`または`component`）
+/// - `components`: コンポーネントの名前と、そのコンポーネントに含まれるファイルのパターン
+/// - `profile`: 名前付きのプロファイル
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectConfig {
+    pub preset: Option<Vec<String>>,
+    pub auto_detect: Option<bool>,
+    pub include: Option<Vec<String>>,
+    pub exclude: Option<Vec<String>>,
+    pub no_default_includes: Option<bool>,
+    pub no_default_excludes: Option<bool>,
+    pub time_window: Option<i64>,
+    pub format: Option<String>,
+    pub top: Option<usize>,
+    #[serde(default)]
+    pub score: ScoreConfig,
+    #[serde(default)]
+    pub authors: BTreeMap<String, Vec<String>>,
+    pub group_by: Option<String>,
+    #[serde(default)]
+    pub components: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub profile: BTreeMap<String, ProjectConfig>,
+}
+
+/// 設定ファイルの`[score]`テーブルの内容を保持する構造体
+///
+/// 各フィールドは`ScoreWeights`の同名のフィールドに対応します。
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ScoreConfig {
+    pub churn: Option<f64>,
+    pub sum_of_coupling: Option<f64>,
+    pub lines_of_code: Option<f64>,
+    pub complexity: Option<f64>,
+    pub cyclomatic_complexity: Option<f64>,
+}
+
+impl ProjectConfig {
+    /// 設定ファイルを読み込みます
+    ///
+    /// # エラー
+    ///
+    /// ファイルの読み込みに失敗した場合、または形式が不正な場合にエラーを返します
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AnalyzerError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            AnalyzerError::InvalidOption(format!(
+                "Failed to read config file {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Self::parse(&content).map_err(|e| match e {
+            AnalyzerError::InvalidOption(message) => {
+                AnalyzerError::InvalidOption(format!("{}: {}", path.display(), message))
+            }
+            e => e,
+        })
+    }
+
+    /// リポジトリのルートにある`hotspot.toml`を読み込みます
+    ///
+    /// ファイルが存在しない場合は`None`を返します。
+    ///
+    /// # エラー
+    ///
+    /// `from_file`と同様の場合にエラーを返します
+    pub fn discover(repo_path: impl AsRef<Path>) -> Result<Option<Self>, AnalyzerError> {
+        let path = repo_path.as_ref().join(CONFIG_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        Self::from_file(path).map(Some)
+    }
+
+    /// 設定ファイルの内容を解析します
+    ///
+    /// # エラー
+    ///
+    /// TOMLとして不正な場合、または未知の項目や型の異なる値が含まれる場合にエラーを返します
+    pub fn parse(content: &str) -> Result<Self, AnalyzerError> {
+        toml::from_str(content).map_err(|e| AnalyzerError::InvalidOption(e.to_string()))
+    }
+
+    /// 指定されたプロファイルの値でファイル全体の設定を上書きした設定を返します
+    ///
+    /// `authors`と`components`は両方の定義を合わせ、同じ名前はプロファイルの定義を優先します。
+    ///
+    /// # エラー
+    ///
+    /// プロファイルが定義されていない場合、またはプロファイルの中に
+    /// さらにプロファイルが定義されている場合にエラーを返します
+    pub fn with_profile(mut self, name: &str) -> Result<Self, AnalyzerError> {
+        let profile = self
+            .profile
+            .remove(name)
+            .ok_or_else(|| AnalyzerError::InvalidOption(format!("Unknown profile: {}", name)))?;
+        if !profile.profile.is_empty() {
+            return Err(AnalyzerError::InvalidOption(format!(
+                "Profile {} must not define profiles",
+                name
+            )));
+        }
+
+        let mut authors = self.authors;
+        authors.extend(profile.authors);
+        let mut components = self.components;
+        components.extend(profile.components);
+
+        Ok(Self {
+            preset: profile.preset.or(self.preset),
+            auto_detect: profile.auto_detect.or(self.auto_detect),
+            include: profile.include.or(self.include),
+            exclude: profile.exclude.or(self.exclude),
+            no_default_includes: profile.no_default_includes.or(self.no_default_includes),
+            no_default_excludes: profile.no_default_excludes.or(self.no_default_excludes),
+            time_window: profile.time_window.or(self.time_window),
+            format: profile.format.or(self.format),
+            top: profile.top.or(self.top),
+            score: ScoreConfig {
+                churn: profile.score.churn.or(self.score.churn),
+                sum_of_coupling: profile.score.sum_of_coupling.or(self.score.sum_of_coupling),
+                lines_of_code: profile.score.lines_of_code.or(self.score.lines_of_code),
+                complexity: profile.score.complexity.or(self.score.complexity),
+                cyclomatic_complexity: profile
+                    .score
+                    .cyclomatic_complexity
+                    .or(self.score.cyclomatic_complexity),
+            },
+            authors,
+            group_by: profile.group_by.or(self.group_by),
+            components,
+            profile: BTreeMap::new(),
+        })
+    }
+
+    /// `authors`の定義を別名として返します
+    pub fn author_aliases(&self) -> AuthorAliases {
+        let mut aliases = AuthorAliases::default();
+        for (canonical, names) in &self.authors {
+            for alias in names {
+                aliases.insert(canonical, alias);
+            }
+        }
+        aliases
+    }
+
+    /// `components`の定義をコンポーネントとして返します
+    pub fn components(&self) -> Vec<Component> {
+        self.components
+            .iter()
+            .map(|(name, patterns)| Component {
+                name: name.clone(),
+                patterns: patterns.clone(),
+            })
+            .collect()
+    }
+
+    /// `[score]`の値で`weights`を上書きしたスコアの重みを返します
+    pub fn score_weights(&self, weights: ScoreWeights) -> ScoreWeights {
+        ScoreWeights {
+            churn: self.score.churn.unwrap_or(weights.churn),
+            sum_of_coupling: self
+                .score
+                .sum_of_coupling
+                .unwrap_or(weights.sum_of_coupling),
+            lines_of_code: self.score.lines_of_code.unwrap_or(weights.lines_of_code),
+            complexity: self.score.complexity.unwrap_or(weights.complexity),
+            cyclomatic_complexity: self
+                .score
+                .cyclomatic_complexity
+                .unwrap_or(weights.cyclomatic_complexity),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+include = ["src/**/*.rs"]
+exclude = ["src/generated/"]
+time_window = 180
+format = "json"
+
+[score]
+churn = 0.5
+sum_of_coupling = 1.0
+complexity = 0.5
+
+[authors]
+"Alice Smith" = ["alice", "alice@example.com"]
+
+[components]
+core = ["src/analyzer/**"]
+
+[profile.backend]
+include = ["server/**/*.go"]
+format = "csv"
+top = 20
+group_by = "component"
+
+[profile.backend.components]
+api = ["server/api/**"]
+
+[profile.backend.authors]
+"Bob" = ["bobby"]
+"#;
+
+    #[test]
+    fn test_parse_config() {
+        let config = ProjectConfig::parse(CONFIG).unwrap();
+
+        assert_eq!(config.include, Some(vec!["src/**/*.rs".to_string()]));
+        assert_eq!(config.time_window, Some(180));
+        assert_eq!(config.top, None);
+        assert_eq!(config.score.churn, Some(0.5));
+        assert_eq!(config.profile.len(), 1);
+        let weights = config.score_weights(ScoreWeights::default());
+        assert_eq!(weights.churn, 0.5);
+        assert_eq!(weights.sum_of_coupling, 1.0);
+        assert_eq!(weights.complexity, 0.5);
+        assert_eq!(weights.lines_of_code, 0.0);
+    }
+
+    #[test]
+    fn test_with_profile() {
+        let config = ProjectConfig::parse(CONFIG)
+            .unwrap()
+            .with_profile("backend")
+            .unwrap();
+
+        assert_eq!(config.include, Some(vec!["server/**/*.go".to_string()]));
+        // プロファイルで指定していない値はファイル全体の設定を引き継ぐ
+        assert_eq!(config.exclude, Some(vec!["src/generated/".to_string()]));
+        assert_eq!(config.time_window, Some(180));
+        assert_eq!(config.format.as_deref(), Some("csv"));
+        assert_eq!(config.top, Some(20));
+        assert_eq!(config.score.churn, Some(0.5));
+        assert_eq!(config.authors.len(), 2);
+        assert_eq!(config.group_by.as_deref(), Some("component"));
+        assert_eq!(
+            config.components(),
+            vec![
+                Component {
+                    name: "api".to_string(),
+                    patterns: vec!["server/api/**".to_string()],
+                },
+                Component {
+                    name: "core".to_string(),
+                    patterns: vec!["src/analyzer/**".to_string()],
+                },
+            ]
+        );
+        assert!(config.profile.is_empty());
+
+        let error = ProjectConfig::parse(CONFIG)
+            .unwrap()
+            .with_profile("frontend")
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid option: Unknown profile: frontend"
+        );
+    }
+
+    #[test]
+    fn test_parse_config_errors() {
+        let error = ProjectConfig::parse("time_window = \"long\"").unwrap_err();
+        assert!(error.to_string().contains("invalid type"));
+
+        let error = ProjectConfig::parse("includes = []").unwrap_err();
+        assert!(error.to_string().contains("unknown field `includes`"));
+
+        let error = ProjectConfig::parse("[score]\nchurn = high").unwrap_err();
+        assert!(error.to_string().contains("line 2"));
+    }
+
+    #[test]
+    fn test_discover_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(ProjectConfig::discover(temp_dir.path()).unwrap(), None);
+
+        std::fs::write(temp_dir.path().join(CONFIG_FILE_NAME), "top = 5\n").unwrap();
+        let config = ProjectConfig::discover(temp_dir.path()).unwrap().unwrap();
+        assert_eq!(config.top, Some(5));
+    }
+}
@@ -0,0 +1,715 @@
+//! 変更の結合度（テンポラルカップリング）を計算するモジュール
+//!
+//! このモジュールは、同じ変更セットで一緒に変更されるファイルの組と、
+//! その結合度を計算する機能を提供します。
+//! 一緒に変更され続けるファイルの組は、コードからは見えない依存関係を示します。
+//! 変更セットはコミットのほか、同じチケットを参照するコミットや、
+//! 同じ開発者が続けて行ったコミット、同じマージコミットで取り込まれたコミットの単位にできます。
+//! 複数のリポジトリのコミットを変更セットにまとめ、リポジトリをまたぐファイルの組の結合度も計算できます。
+
+use super::error::AnalyzerError;
+use super::metrics::FileCoupling;
+use super::options::CouplingOptions;
+use chrono::{DateTime, Duration, Utc};
+use indexmap::IndexMap;
+use regex::Regex;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// コミットメッセージからチケットの識別子（`PROJ-123`など）を抽出するデフォルトのパターン
+pub const DEFAULT_TICKET_PATTERN: &str = r"\b[A-Z][A-Z0-9]+-[0-9]+\b";
+
+/// チケットの識別子と同じ形式でも、チケットとして扱わない接頭辞
+///
+/// 文字コード（`UTF-8`）やハッシュ関数（`SHA-256`）、規格（`ISO-8601`、`RFC-3339`）、
+/// ライセンス（`GPL-3`）などの名前を、チケットの参照と誤認しないようにします。
+const NON_TICKET_PREFIXES: &[&str] = &[
+    "UTF", "UCS", "SHA", "CRC", "AES", "RSA", "ISO", "IEC", "IEEE", "RFC", "ECMA", "ES", "CVE",
+    "CWE", "HTTP", "TLS", "SSL", "GPL", "LGPL", "AGPL", "BSD", "MPL",
+];
+
+/// 変更の結合度を計算する変更セットの単位を表す列挙型
+///
+/// - `Commit`: 1つのコミット（`commit`）
+/// - `Ticket`: コミットメッセージで同じチケットを参照するコミット（`ticket`）
+/// - `Author`: 同じ開発者が指定された分数以内の間隔で続けて行ったコミット（`author:N`）
+/// - `Merge`: 同じマージコミットで取り込まれたコミット（`merge`）
+/// - `Window`: 開発者によらず、指定された分数以内の間隔で続けて行われたコミット（`window:N`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChangeSetKind {
+    #[default]
+    Commit,
+    Ticket,
+    Author(u32),
+    Merge,
+    Window(u32),
+}
+
+impl FromStr for ChangeSetKind {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "commit" => return Ok(Self::Commit),
+            "ticket" => return Ok(Self::Ticket),
+            "merge" => return Ok(Self::Merge),
+            _ => {}
+        }
+        let minutes = |prefix: &str| {
+            s.strip_prefix(prefix)
+                .and_then(|minutes| minutes.parse::<u32>().ok())
+                .filter(|minutes| *minutes > 0)
+        };
+        minutes("author:")
+            .map(Self::Author)
+            .or_else(|| minutes("window:").map(Self::Window))
+            .ok_or_else(|| {
+                AnalyzerError::InvalidOption(format!(
+                    "Unknown change set: {} (expected commit, ticket, author:N, merge or window:N)",
+                    s
+                ))
+            })
+    }
+}
+
+/// 変更セットにまとめる前の、1つのコミットで変更されたファイルを保持する構造体
+///
+/// # フィールド
+///
+/// - `id`: コミットのハッシュ
+/// - `author`: コミット作成者の名前
+/// - `timestamp`: コミット日時
+/// - `message`: コミットメッセージ
+/// - `files`: コミットで変更されたファイルのパス
+pub(crate) struct CommitChanges {
+    pub(crate) id: String,
+    pub(crate) author: String,
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) message: String,
+    pub(crate) files: Vec<String>,
+}
+
+/// コミットを`change_set`で指定された単位の変更セットにまとめ、変更セットごとのファイルのリストを返します
+///
+/// チケットを参照していないコミットと、マージコミットで取り込まれていないコミットは、
+/// それぞれ単独で1つの変更セットになります。複数のチケットを参照するコミットは、
+/// それぞれのチケットの変更セットに含めます。`UTF-8`や`SHA-256`のように
+/// `NON_TICKET_PREFIXES`の接頭辞で始まる識別子は、チケットとして扱いません。
+/// マージ単位では、`merged_commits`でコミットを取り込んだマージコミットを判定します。
+///
+/// # エラー
+///
+/// チケットのパターンが正規表現として不正な場合にエラーを返します
+pub(crate) fn change_sets(
+    commits: Vec<CommitChanges>,
+    options: &CouplingOptions,
+    merged_commits: &HashMap<String, String>,
+) -> Result<Vec<Vec<String>>, AnalyzerError> {
+    let keys: Vec<Vec<String>> = match options.change_set {
+        ChangeSetKind::Commit => commits
+            .iter()
+            .map(|commit| vec![commit.id.clone()])
+            .collect(),
+        ChangeSetKind::Ticket => {
+            let pattern = Regex::new(&options.ticket_pattern)
+                .map_err(|e| AnalyzerError::InvalidPattern(e.to_string()))?;
+            commits
+                .iter()
+                .map(|commit| {
+                    let mut tickets: Vec<String> = Vec::new();
+                    for ticket in pattern.find_iter(&commit.message) {
+                        let ticket = ticket.as_str();
+                        let key = format!("ticket:{}", ticket);
+                        if is_ticket(ticket) && !tickets.contains(&key) {
+                            tickets.push(key);
+                        }
+                    }
+                    if tickets.is_empty() {
+                        tickets.push(commit.id.clone());
+                    }
+                    tickets
+                })
+                .collect()
+        }
+        ChangeSetKind::Author(minutes) => sessions(&commits, minutes, true)
+            .into_iter()
+            .map(|key| vec![key])
+            .collect(),
+        ChangeSetKind::Window(minutes) => sessions(&commits, minutes, false)
+            .into_iter()
+            .map(|key| vec![key])
+            .collect(),
+        ChangeSetKind::Merge => commits
+            .iter()
+            .map(|commit| vec![merged_commits.get(&commit.id).unwrap_or(&commit.id).clone()])
+            .collect(),
+    };
+
+    let mut change_sets: IndexMap<String, Vec<String>> = IndexMap::new();
+    for (keys, commit) in keys.into_iter().zip(commits) {
+        for key in keys {
+            change_sets
+                .entry(key)
+                .or_default()
+                .extend(commit.files.iter().cloned());
+        }
+    }
+    Ok(change_sets.into_values().collect())
+}
+
+/// チケットのパターンに一致した文字列が、チケットとして扱わない接頭辞で始まっていないかを判定します
+fn is_ticket(ticket: &str) -> bool {
+    match ticket.split_once('-') {
+        Some((prefix, _)) => !NON_TICKET_PREFIXES.contains(&prefix),
+        None => true,
+    }
+}
+
+/// コミットを、間隔が`minutes`分以内で続く一連のコミットにまとめます
+///
+/// `by_author`が`true`の場合は、同じ開発者のコミットだけをまとめます。
+/// 各コミットについて、そのコミットが属する一連のコミットの最初のコミットのハッシュを返します。
+fn sessions(commits: &[CommitChanges], minutes: u32, by_author: bool) -> Vec<String> {
+    let window = Duration::minutes(minutes as i64);
+    let same_author = |a: usize, b: usize| !by_author || commits[a].author == commits[b].author;
+    let mut order: Vec<usize> = (0..commits.len()).collect();
+    order.sort_by(|&a, &b| {
+        let author = if by_author {
+            commits[a].author.cmp(&commits[b].author)
+        } else {
+            std::cmp::Ordering::Equal
+        };
+        author.then(commits[a].timestamp.cmp(&commits[b].timestamp))
+    });
+
+    let mut keys = vec![String::new(); commits.len()];
+    let mut previous: Option<usize> = None;
+    for index in order {
+        let commit = &commits[index];
+        let key = match previous {
+            Some(previous)
+                if same_author(previous, index)
+                    && commit.timestamp - commits[previous].timestamp <= window =>
+            {
+                keys[previous].clone()
+            }
+            _ => format!("session:{}", commit.id),
+        };
+        keys[index] = key;
+        previous = Some(index);
+    }
+    keys
+}
+
+/// 1つのリポジトリの、変更セットにまとめる前のコミットを保持する構造体
+///
+/// # フィールド
+///
+/// - `name`: 結果でリポジトリを識別する名前
+/// - `commits`: リポジトリのコミットで変更されたファイル
+/// - `merged_commits`: マージ単位で使用する、コミットとそれを取り込んだマージコミットの対応
+pub(crate) struct RepositoryChanges {
+    pub(crate) name: String,
+    pub(crate) commits: Vec<CommitChanges>,
+    pub(crate) merged_commits: HashMap<String, String>,
+}
+
+/// 複数のリポジトリのコミットをまとめて変更セットにし、リポジトリをまたぐファイルの組の結合度を計算します
+///
+/// コミットは`change_set`で指定された単位でリポジトリをまたいでまとめるため、
+/// 同じチケットを参照するコミット（`Ticket`）や、近い日時に行われたコミット（`Window`、`Author`）が
+/// 1つの変更セットになります。同じリポジトリ内のファイルの組は結果に含めません。
+///
+/// 結果は`file_couplings`と同じ順に並べ、各組の`repository`は`coupled_repository`より
+/// `repositories`で前に指定したリポジトリとします。
+///
+/// # エラー
+///
+/// チケットのパターンが正規表現として不正な場合にエラーを返します
+pub(crate) fn cross_repository_couplings(
+    repositories: Vec<RepositoryChanges>,
+    options: &CouplingOptions,
+) -> Result<Vec<FileCoupling>, AnalyzerError> {
+    let mut names = Vec::with_capacity(repositories.len());
+    let mut commits = Vec::new();
+    let mut merged_commits = HashMap::new();
+    // 異なるリポジトリの同じパスやハッシュを区別するため、リポジトリの番号を前に付ける
+    for (index, repository) in repositories.into_iter().enumerate() {
+        let key = |value: &str| format!("{}:{}", index, value);
+        names.push(repository.name);
+        for (commit, merge) in &repository.merged_commits {
+            merged_commits.insert(key(commit), key(merge));
+        }
+        for mut commit in repository.commits {
+            commit.id = key(&commit.id);
+            commit.files = commit.files.iter().map(|path| key(path)).collect();
+            commits.push(commit);
+        }
+    }
+
+    let split = |key: &str| -> (usize, String) {
+        let (index, path) = key.split_once(':').unwrap();
+        (index.parse().unwrap(), path.to_string())
+    };
+    let change_sets = change_sets(commits, options, &merged_commits)?;
+    Ok(file_couplings(&change_sets, options)
+        .into_iter()
+        .filter_map(|coupling| {
+            let (a, path) = split(&coupling.path);
+            let (b, coupled_path) = split(&coupling.coupled_path);
+            if a == b {
+                return None;
+            }
+            let coupling = FileCoupling {
+                path,
+                coupled_path,
+                repository: Some(names[a].clone()),
+                coupled_repository: Some(names[b].clone()),
+                ..coupling
+            };
+            Some(if a < b {
+                coupling
+            } else {
+                FileCoupling {
+                    path: coupling.coupled_path,
+                    coupled_path: coupling.path,
+                    revisions: coupling.coupled_revisions,
+                    coupled_revisions: coupling.revisions,
+                    repository: coupling.coupled_repository,
+                    coupled_repository: coupling.repository,
+                    ..coupling
+                }
+            })
+        })
+        .collect())
+}
+
+/// 変更セットごとに変更されたファイルのリストから、各ファイルの`sum_of_coupling`を計算します
+///
+/// ファイルを含む変更セットごとに、一緒に変更された他のファイルの数を合計します。
+/// `file_couplings`と同様に、`max_changeset_size`より多くのファイルを含む変更セットは数えません。
+pub(crate) fn sum_of_coupling(
+    change_sets: &[Vec<String>],
+    options: &CouplingOptions,
+) -> HashMap<String, u64> {
+    let mut sums: HashMap<String, u64> = HashMap::new();
+    for files in change_sets {
+        let mut changed: Vec<&str> = files.iter().map(String::as_str).collect();
+        changed.sort_unstable();
+        changed.dedup();
+        if options
+            .max_changeset_size
+            .is_some_and(|max| changed.len() > max)
+        {
+            continue;
+        }
+        for path in &changed {
+            *sums.entry(path.to_string()).or_insert(0) += (changed.len() - 1) as u64;
+        }
+    }
+    sums
+}
+
+/// 変更セットごとに変更されたファイルのリストから、ファイルの組の結合度を計算します
+///
+/// ファイルの変更回数は、そのファイルを含む変更セットの数です。
+/// `max_changeset_size`より多くのファイルを含む変更セットは、変更回数と結合度のどちらにも数えません。
+///
+/// 結果は結合度の高い順（同じ場合は一緒に変更された回数の多い順、パスの順）に並べ、
+/// 各組の`path`は`coupled_path`より辞書順で前のパスとします。
+pub(crate) fn file_couplings(
+    change_sets: &[Vec<String>],
+    options: &CouplingOptions,
+) -> Vec<FileCoupling> {
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut paths: Vec<&str> = Vec::new();
+    let mut revisions: Vec<u32> = Vec::new();
+    let mut shared_commits: HashMap<(usize, usize), u32> = HashMap::new();
+
+    for files in change_sets {
+        let mut changed: Vec<usize> = files
+            .iter()
+            .map(|path| {
+                *ids.entry(path).or_insert_with(|| {
+                    paths.push(path);
+                    revisions.push(0);
+                    paths.len() - 1
+                })
+            })
+            .collect();
+        changed.sort_unstable();
+        changed.dedup();
+        if options
+            .max_changeset_size
+            .is_some_and(|max| changed.len() > max)
+        {
+            continue;
+        }
+
+        for (i, &file) in changed.iter().enumerate() {
+            revisions[file] += 1;
+            for &coupled in &changed[i + 1..] {
+                *shared_commits.entry((file, coupled)).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut couplings: Vec<FileCoupling> = shared_commits
+        .into_iter()
+        .filter(|&((a, b), shared)| {
+            shared >= options.min_shared_commits
+                && revisions[a] >= options.min_revisions
+                && revisions[b] >= options.min_revisions
+        })
+        .map(|((a, b), shared)| {
+            let (a, b) = if paths[a] <= paths[b] { (a, b) } else { (b, a) };
+            let average_revisions = (revisions[a] + revisions[b]) as f64 / 2.0;
+            FileCoupling {
+                path: paths[a].to_string(),
+                coupled_path: paths[b].to_string(),
+                shared_commits: shared,
+                degree: shared as f64 / average_revisions,
+                revisions: revisions[a],
+                coupled_revisions: revisions[b],
+                repository: None,
+                coupled_repository: None,
+            }
+        })
+        .filter(|coupling| coupling.degree >= options.min_degree)
+        .collect();
+
+    couplings.sort_by(|a, b| {
+        b.degree
+            .partial_cmp(&a.degree)
+            .unwrap()
+            .then(b.shared_commits.cmp(&a.shared_commits))
+            .then_with(|| a.path.cmp(&b.path))
+            .then_with(|| a.coupled_path.cmp(&b.coupled_path))
+    });
+    couplings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change_sets(sets: &[&[&str]]) -> Vec<Vec<String>> {
+        sets.iter()
+            .map(|files| files.iter().map(|path| path.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_file_couplings() {
+        let sets = change_sets(&[
+            &["src/b.rs", "src/a.rs"],
+            &["src/a.rs", "src/b.rs", "src/c.rs"],
+            &["src/a.rs", "src/b.rs"],
+            &["src/a.rs", "src/c.rs"],
+            &["src/c.rs"],
+        ]);
+        let options = CouplingOptions {
+            min_revisions: 1,
+            min_shared_commits: 1,
+            min_degree: 0.0,
+            max_changeset_size: None,
+            ..Default::default()
+        };
+
+        let couplings = file_couplings(&sets, &options);
+        let summary: Vec<_> = couplings
+            .iter()
+            .map(|c| (c.path.as_str(), c.coupled_path.as_str(), c.shared_commits))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("src/a.rs", "src/b.rs", 3),
+                ("src/a.rs", "src/c.rs", 2),
+                ("src/b.rs", "src/c.rs", 1),
+            ]
+        );
+        // 一緒に変更された回数 / 変更回数の平均
+        assert!((couplings[0].degree - 3.0 / 3.5).abs() < 1e-10);
+        assert_eq!(
+            (couplings[0].revisions, couplings[0].coupled_revisions),
+            (4, 3)
+        );
+        assert!((couplings[1].degree - 2.0 / 3.5).abs() < 1e-10);
+
+        // しきい値を満たさない組は報告しない
+        let options = CouplingOptions {
+            min_revisions: 4,
+            min_shared_commits: 2,
+            min_degree: 0.0,
+            max_changeset_size: None,
+            ..Default::default()
+        };
+        assert!(file_couplings(&sets, &options).is_empty());
+
+        let options = CouplingOptions {
+            min_revisions: 1,
+            min_shared_commits: 2,
+            min_degree: 0.6,
+            max_changeset_size: None,
+            ..Default::default()
+        };
+        assert_eq!(file_couplings(&sets, &options).len(), 1);
+    }
+
+    #[test]
+    fn test_file_couplings_skips_large_change_sets() {
+        let sets = change_sets(&[
+            &["a.rs", "b.rs"],
+            &["a.rs", "b.rs", "c.rs", "d.rs"],
+            &["a.rs", "b.rs", "a.rs"],
+        ]);
+        let options = CouplingOptions {
+            min_revisions: 1,
+            min_shared_commits: 1,
+            min_degree: 0.0,
+            max_changeset_size: Some(3),
+            ..Default::default()
+        };
+
+        let couplings = file_couplings(&sets, &options);
+        assert_eq!(couplings.len(), 1);
+        assert_eq!(couplings[0].shared_commits, 2);
+        assert_eq!(couplings[0].revisions, 2);
+        assert!((couplings[0].degree - 1.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_sum_of_coupling() {
+        let sets: Vec<Vec<String>> = vec![
+            vec!["a.rs".into(), "b.rs".into(), "c.rs".into()],
+            vec!["a.rs".into(), "b.rs".into(), "a.rs".into()],
+            vec!["a.rs".into()],
+            vec!["a.rs".into(), "b.rs".into(), "c.rs".into(), "d.rs".into()],
+        ];
+        let options = CouplingOptions {
+            max_changeset_size: Some(3),
+            ..Default::default()
+        };
+
+        let sums = sum_of_coupling(&sets, &options);
+        assert_eq!(sums["a.rs"], 3);
+        assert_eq!(sums["b.rs"], 3);
+        assert_eq!(sums["c.rs"], 2);
+        assert!(!sums.contains_key("d.rs"));
+    }
+
+    fn commit(id: &str, author: &str, minute: i64, message: &str, files: &[&str]) -> CommitChanges {
+        CommitChanges {
+            id: id.to_string(),
+            author: author.to_string(),
+            timestamp: DateTime::from_timestamp(minute * 60, 0).unwrap(),
+            message: message.to_string(),
+            files: files.iter().map(|path| path.to_string()).collect(),
+        }
+    }
+
+    fn grouped(
+        change_set: ChangeSetKind,
+        merged_commits: &HashMap<String, String>,
+    ) -> Vec<Vec<String>> {
+        let commits = vec![
+            commit("c5", "alice", 200, "PROJ-12: fix", &["e.rs"]),
+            commit("c4", "bob", 40, "Refs #3", &["d.rs"]),
+            commit("c3", "alice", 30, "PROJ-7 follow-up", &["c.rs"]),
+            commit("c2", "bob", 20, "[PROJ-12] api", &["b.rs"]),
+            commit("c1", "alice", 0, "PROJ-12 model", &["a.rs"]),
+        ];
+        let options = CouplingOptions {
+            change_set,
+            ..Default::default()
+        };
+        let mut sets = super::change_sets(commits, &options, merged_commits).unwrap();
+        for files in &mut sets {
+            files.sort();
+        }
+        sets.sort();
+        sets
+    }
+
+    #[test]
+    fn test_change_set_kind_from_str() {
+        assert_eq!(
+            "commit".parse::<ChangeSetKind>().unwrap(),
+            ChangeSetKind::Commit
+        );
+        assert_eq!(
+            "ticket".parse::<ChangeSetKind>().unwrap(),
+            ChangeSetKind::Ticket
+        );
+        assert_eq!(
+            "author:30".parse::<ChangeSetKind>().unwrap(),
+            ChangeSetKind::Author(30)
+        );
+        assert_eq!(
+            "merge".parse::<ChangeSetKind>().unwrap(),
+            ChangeSetKind::Merge
+        );
+        assert_eq!(
+            "window:60".parse::<ChangeSetKind>().unwrap(),
+            ChangeSetKind::Window(60)
+        );
+        assert!("author".parse::<ChangeSetKind>().is_err());
+        assert!("window:0".parse::<ChangeSetKind>().is_err());
+        assert!("author:0".parse::<ChangeSetKind>().is_err());
+        assert!("branch".parse::<ChangeSetKind>().is_err());
+    }
+
+    #[test]
+    fn test_change_sets() {
+        let none = HashMap::new();
+        assert_eq!(grouped(ChangeSetKind::Commit, &none).len(), 5);
+
+        // 同じチケットを参照するコミットをまとめ、チケットの無いコミットは単独にする
+        assert_eq!(
+            grouped(ChangeSetKind::Ticket, &none),
+            vec![vec!["a.rs", "b.rs", "e.rs"], vec!["c.rs"], vec!["d.rs"],]
+        );
+
+        // 同じ開発者の30分以内の間隔で続くコミットをまとめる
+        assert_eq!(
+            grouped(ChangeSetKind::Author(30), &none),
+            vec![vec!["a.rs", "c.rs"], vec!["b.rs", "d.rs"], vec!["e.rs"]]
+        );
+
+        // 開発者によらず15分以内の間隔で続くコミットをまとめる
+        assert_eq!(
+            grouped(ChangeSetKind::Window(15), &none),
+            vec![vec!["a.rs"], vec!["b.rs", "c.rs", "d.rs"], vec!["e.rs"]]
+        );
+
+        // 同じマージコミットで取り込まれたコミットをまとめる
+        let merged = HashMap::from([
+            ("c2".to_string(), "m1".to_string()),
+            ("c3".to_string(), "m1".to_string()),
+        ]);
+        assert_eq!(
+            grouped(ChangeSetKind::Merge, &merged),
+            vec![
+                vec!["a.rs"],
+                vec!["b.rs", "c.rs"],
+                vec!["d.rs"],
+                vec!["e.rs"],
+            ]
+        );
+
+        // 不正なチケットのパターン
+        let options = CouplingOptions {
+            change_set: ChangeSetKind::Ticket,
+            ticket_pattern: "(".to_string(),
+            ..Default::default()
+        };
+        assert!(super::change_sets(Vec::new(), &options, &none).is_err());
+    }
+
+    #[test]
+    fn test_ticket_change_sets() {
+        let commits = vec![
+            commit(
+                "c4",
+                "alice",
+                30,
+                "Parse dates as ISO-8601 (PROJ-2)",
+                &["d.rs"],
+            ),
+            commit(
+                "c3",
+                "bob",
+                20,
+                "Hash with SHA-256 and read UTF-8",
+                &["c.rs"],
+            ),
+            commit("c2", "bob", 10, "PROJ-1, PROJ-2: shared client", &["b.rs"]),
+            commit("c1", "alice", 0, "PROJ-1 PROJ-1 model", &["a.rs"]),
+        ];
+        let options = CouplingOptions {
+            change_set: ChangeSetKind::Ticket,
+            ..Default::default()
+        };
+        let mut sets = super::change_sets(commits, &options, &HashMap::new()).unwrap();
+        for files in &mut sets {
+            files.sort();
+        }
+        sets.sort();
+
+        // 複数のチケットを参照するコミットはそれぞれのチケットに含め、
+        // 規格や文字コードの名前はチケットとして扱わない
+        assert_eq!(
+            sets,
+            vec![vec!["a.rs", "b.rs"], vec!["b.rs", "d.rs"], vec!["c.rs"],]
+        );
+        assert!(is_ticket("PROJ-1"));
+        assert!(!is_ticket("UTF-8"));
+        assert!(!is_ticket("RFC-3339"));
+    }
+
+    #[test]
+    fn test_cross_repository_couplings() {
+        let repositories = vec![
+            RepositoryChanges {
+                name: "frontend".to_string(),
+                commits: vec![
+                    commit("f3", "alice", 100, "PROJ-2 form", &["src/form.ts"]),
+                    commit("f2", "alice", 50, "PROJ-1 retry", &["src/api.ts"]),
+                    commit(
+                        "f1",
+                        "alice",
+                        0,
+                        "PROJ-1 client",
+                        &["src/api.ts", "src/ui.ts"],
+                    ),
+                ],
+                merged_commits: HashMap::new(),
+            },
+            RepositoryChanges {
+                name: "backend".to_string(),
+                commits: vec![
+                    commit("b2", "bob", 95, "PROJ-2 validation", &["src/api.rs"]),
+                    commit("b1", "bob", 10, "[PROJ-1] endpoint", &["src/api.rs"]),
+                ],
+                merged_commits: HashMap::new(),
+            },
+        ];
+        let options = CouplingOptions {
+            min_revisions: 1,
+            min_shared_commits: 1,
+            min_degree: 0.0,
+            max_changeset_size: None,
+            change_set: ChangeSetKind::Ticket,
+            ..Default::default()
+        };
+
+        // 同じチケットのコミットをまとめ、リポジトリをまたぐ組だけを報告する
+        let couplings = cross_repository_couplings(repositories, &options).unwrap();
+        let summary: Vec<_> = couplings
+            .iter()
+            .map(|c| {
+                (
+                    c.repository.as_deref().unwrap(),
+                    c.path.as_str(),
+                    c.coupled_repository.as_deref().unwrap(),
+                    c.coupled_path.as_str(),
+                    c.shared_commits,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("frontend", "src/api.ts", "backend", "src/api.rs", 1),
+                ("frontend", "src/form.ts", "backend", "src/api.rs", 1),
+                ("frontend", "src/ui.ts", "backend", "src/api.rs", 1),
+            ]
+        );
+        // 前に指定したリポジトリのファイルの変更回数が`revisions`になる
+        assert_eq!(
+            (couplings[0].revisions, couplings[0].coupled_revisions),
+            (1, 2)
+        );
+    }
+}
@@ -0,0 +1,134 @@
+//! 日時指定の解析を担当するモジュール
+//!
+//! このモジュールは、分析期間の指定に使用する日時文字列を解析する機能を提供します。
+//! ISO 8601形式の絶対日時と、`2 weeks ago`のようなgit形式の相対日時に対応します。
+
+use super::error::AnalyzerError;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Utc};
+use regex::Regex;
+
+/// 日時文字列を解析します
+///
+/// 相対日時は`reference`を基準に解決されます。タイムゾーンを含まない日時はUTCとして扱います。
+///
+/// # 対応する形式
+///
+/// - `2024-01-31T12:00:00+09:00`（RFC 3339）
+/// - `2024-01-31T12:00:00`、`2024-01-31 12:00:00`
+/// - `2024-01-31`（その日の0時）
+/// - `@1706659200`（UNIXタイムスタンプ）
+/// - `now`、`yesterday`
+/// - `3 days ago`、`2.weeks.ago`など（second、minute、hour、day、week、month、year）
+///
+/// # エラー
+///
+/// いずれの形式にも一致しない場合に`TimestampError`を返します
+pub fn parse_date(input: &str, reference: DateTime<Utc>) -> Result<DateTime<Utc>, AnalyzerError> {
+    let input = input.trim();
+    let invalid = || AnalyzerError::TimestampError(format!("Unsupported date format: {}", input));
+
+    match input.to_lowercase().as_str() {
+        "now" => return Ok(reference),
+        "yesterday" => return Ok(reference - Duration::days(1)),
+        _ => {}
+    }
+
+    if let Some(seconds) = input.strip_prefix('@') {
+        let seconds: i64 = seconds.parse().map_err(|_| invalid())?;
+        return DateTime::from_timestamp(seconds, 0).ok_or_else(invalid);
+    }
+
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(date_time.with_timezone(&Utc));
+    }
+
+    for format in ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"] {
+        if let Ok(date_time) = NaiveDateTime::parse_from_str(input, format) {
+            return Ok(date_time.and_utc());
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return Ok(date.and_hms_opt(0, 0, 0).ok_or_else(invalid)?.and_utc());
+    }
+
+    parse_relative_date(input, reference).ok_or_else(invalid)
+}
+
+/// `3 days ago`や`2.weeks.ago`のような相対日時を解析します
+///
+/// 月は30日、年は365日として扱います。
+fn parse_relative_date(input: &str, reference: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let pattern =
+        Regex::new(r"(?i)^(\d+)[\s.]+(second|sec|minute|min|hour|day|week|month|year)s?[\s.]+ago$")
+            .ok()?;
+    let captures = pattern.captures(input)?;
+
+    let amount: i64 = captures[1].parse().ok()?;
+    let unit = captures[2].to_lowercase();
+    let duration = match unit.as_str() {
+        "second" | "sec" => Duration::try_seconds(amount)?,
+        "minute" | "min" => Duration::try_minutes(amount)?,
+        "hour" => Duration::try_hours(amount)?,
+        "day" => Duration::try_days(amount)?,
+        "week" => Duration::try_weeks(amount)?,
+        "month" => Duration::try_days(amount.checked_mul(30)?)?,
+        "year" => Duration::try_days(amount.checked_mul(365)?)?,
+        _ => return None,
+    };
+
+    reference.checked_sub_signed(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reference() -> DateTime<Utc> {
+        parse_date("2024-03-01T00:00:00Z", Utc::now()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_absolute_dates() {
+        let cases = [
+            ("2024-01-31", "2024-01-31T00:00:00+00:00"),
+            ("2024-01-31 12:34:56", "2024-01-31T12:34:56+00:00"),
+            ("2024-01-31T12:34:56", "2024-01-31T12:34:56+00:00"),
+            ("2024-01-31T12:34:56+09:00", "2024-01-31T03:34:56+00:00"),
+            ("@1706659200", "2024-01-31T00:00:00+00:00"),
+        ];
+
+        for (input, expected) in cases {
+            let result = parse_date(input, reference()).unwrap();
+            assert_eq!(result.to_rfc3339(), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_relative_dates() {
+        let cases = [
+            ("now", "2024-03-01T00:00:00+00:00"),
+            ("yesterday", "2024-02-29T00:00:00+00:00"),
+            ("2 days ago", "2024-02-28T00:00:00+00:00"),
+            ("1.week.ago", "2024-02-23T00:00:00+00:00"),
+            ("3 Hours Ago", "2024-02-29T21:00:00+00:00"),
+            ("1 month ago", "2024-01-31T00:00:00+00:00"),
+            ("1 year ago", "2023-03-02T00:00:00+00:00"),
+        ];
+
+        for (input, expected) in cases {
+            let result = parse_date(input, reference()).unwrap();
+            assert_eq!(result.to_rfc3339(), expected, "input: {}", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid_dates() {
+        for input in ["", "last tuesday", "2024-13-01", "@abc", "days ago"] {
+            match parse_date(input, reference()) {
+                Err(AnalyzerError::TimestampError(_)) => (),
+                other => panic!("Expected TimestampError for {:?}, got {:?}", input, other),
+            }
+        }
+    }
+}
@@ -0,0 +1,602 @@
+//! コミットの差分から変更ファイルを抽出するモジュール
+//!
+//! このモジュールは、分析対象とするファイルの判定と、
+//! コミットごとの差分からファイルの変更内容を取り出す機能を提供します。
+
+use super::cache;
+use super::error::AnalyzerError;
+use super::glob::{expand_braces, GlobSet};
+use super::ignore::RepositoryRules;
+use super::options::AnalyzerOptions;
+use git2::{Commit, Delta, DiffFindOptions, DiffOptions, Patch, Repository, Tree};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 自動生成されたファイルの先頭に含まれる目印
+///
+/// `skip_generated`が有効な場合、ファイルの先頭`GENERATED_HEADER_BYTES`バイトに
+/// いずれかの正規表現が一致するファイルを分析から除外します。
+pub const GENERATED_FILE_MARKERS: &[&str] = &[
+    // Goの規約（protoc-gen-go、stringer、mockgenなど）
+    r"Code generated .* DO NOT EDIT",
+    r"@generated",
+    // .NETのツール
+    r"<auto-generated",
+    r"(?i)generated by the protocol buffer compiler",
+    r"(?i)this file (is|was) (automatically|auto-?)generated",
+];
+
+/// 自動生成されたファイルの目印を探す、ファイルの先頭からのバイト数
+const GENERATED_HEADER_BYTES: usize = 1024;
+
+/// 分析対象の範囲をまたぐリネームの検出で、類似度を比較するファイルの数の上限
+///
+/// 上限を超える数のファイルを追加・削除したコミットでは、範囲をまたぐリネームを検出しません。
+const CROSS_SCOPE_RENAME_LIMIT: usize = 1000;
+
+/// コミット内の1ファイル分の変更を表す構造体
+///
+/// # フィールド
+///
+/// - `path`: 変更後のファイルパス
+/// - `old_path`: リネームされた場合の変更前のパス
+/// - `deleted`: ファイルが削除された場合は`true`（`path`は削除前のパス）
+/// - `lines_added`: 追加された行数
+/// - `lines_deleted`: 削除された行数
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: String,
+    pub old_path: Option<String>,
+    pub deleted: bool,
+    pub lines_added: u32,
+    pub lines_deleted: u32,
+}
+
+/// 差分から除外したファイルを表す構造体
+///
+/// # フィールド
+///
+/// - `path`: ファイルパス
+/// - `kind`: 除外した理由
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub(crate) struct SkippedFile {
+    pub(crate) path: String,
+    pub(crate) kind: SkippedFileKind,
+}
+
+/// 差分から除外したファイルの種類を表す列挙型
+///
+/// - `Binary`: バイナリファイル
+/// - `Generated`: 先頭に自動生成の目印を含むファイル
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SkippedFileKind {
+    Binary,
+    Generated,
+}
+
+/// コミットで変更されたファイルの抽出結果を保持する構造体
+///
+/// # フィールド
+///
+/// - `files`: 分析対象ファイルの変更内容
+/// - `skipped`: 分析対象のパターンに一致したが、バイナリや自動生成のため除外したファイル
+#[derive(Debug, Default)]
+pub(crate) struct ChangedFiles {
+    pub(crate) files: Vec<FileChange>,
+    pub(crate) skipped: Vec<SkippedFile>,
+}
+
+/// マージコミットの差分の計算方法を表す列挙型
+///
+/// - `Skip`: マージコミットを分析対象から除外する
+/// - `FirstParent`: 第1親との差分を使用する（マージされたブランチの変更を全て含む）
+/// - `AllParents`: いずれかの親と異なるファイルを変更として扱う
+/// - `Combined`: 全ての親と異なるファイル（コンフリクトの解消などマージ時の変更）のみを扱う
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MergeMode {
+    #[default]
+    Skip,
+    FirstParent,
+    AllParents,
+    Combined,
+}
+
+impl FromStr for MergeMode {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(Self::Skip),
+            "first-parent" => Ok(Self::FirstParent),
+            "all-parents" => Ok(Self::AllParents),
+            "combined" => Ok(Self::Combined),
+            _ => Err(AnalyzerError::InvalidOption(format!(
+                "Unknown merge mode: {} (expected skip, first-parent, all-parents or combined)",
+                s
+            ))),
+        }
+    }
+}
+
+/// 差分の計算方法と分析対象ファイルの条件を保持する構造体
+///
+/// リポジトリハンドルを持たないため、複数のスレッドから共有できます。
+///
+/// # フィールド
+///
+/// - `include_patterns`: 分析対象とするファイルパターン（gitignore形式）
+/// - `exclude_patterns`: 分析から除外するファイルパターン（gitignore形式）
+/// - `repository_rules`: リポジトリの`.gitignore`などから読み込んだ除外ルール
+/// - `scope_paths`: 分析対象とするディレクトリまたはファイル
+/// - `pathspecs`: 差分の計算時にlibgit2へ渡すパススペック
+/// - `rename_threshold`: リネーム・コピー検出の類似度しきい値（%）
+/// - `renames_across_scope`: パススペックの範囲の内外の間で移動したファイルもリネームとして検出するかどうか
+/// - `merge_mode`: マージコミットの差分の計算方法
+/// - `skip_binary`: バイナリファイルを除外するかどうか
+/// - `generated_markers`: 自動生成されたファイルの目印。`None`の場合は自動生成されたファイルを除外しない
+pub(crate) struct ChangeExtractor {
+    include_patterns: GlobSet,
+    exclude_patterns: GlobSet,
+    repository_rules: RepositoryRules,
+    scope_paths: Vec<String>,
+    pathspecs: Vec<String>,
+    rename_threshold: Option<u16>,
+    renames_across_scope: bool,
+    merge_mode: MergeMode,
+    skip_binary: bool,
+    generated_markers: Option<Regex>,
+}
+
+impl ChangeExtractor {
+    /// 分析オプションとリポジトリの除外ルールからChangeExtractorインスタンスを作成します
+    ///
+    /// # エラー
+    ///
+    /// パターンの正規表現への変換に失敗した場合にエラーを返します
+    pub(crate) fn new(
+        options: &AnalyzerOptions,
+        repository_rules: RepositoryRules,
+    ) -> Result<Self, AnalyzerError> {
+        let scope_paths: Vec<String> = options
+            .paths
+            .iter()
+            .map(|path| normalize_scope_path(path))
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        // 対象ディレクトリが指定されている場合はそれを、そうでなければ
+        // インクルードパターンをパススペックとして差分の計算対象を絞り込む
+        let pathspecs = if scope_paths.is_empty() {
+            include_pathspecs(&options.include_patterns)
+        } else {
+            scope_paths.clone()
+        };
+
+        Ok(Self {
+            include_patterns: GlobSet::new(&options.include_patterns)?,
+            exclude_patterns: GlobSet::new(&options.exclude_patterns)?,
+            repository_rules,
+            scope_paths,
+            pathspecs,
+            rename_threshold: options.rename_threshold,
+            renames_across_scope: options.renames_across_scope,
+            merge_mode: options.merge_mode,
+            skip_binary: options.skip_binary,
+            generated_markers: options
+                .skip_generated
+                .then(|| Regex::new(&GENERATED_FILE_MARKERS.join("|")))
+                .transpose()
+                .map_err(|e| AnalyzerError::InvalidPattern(e.to_string()))?,
+        })
+    }
+
+    /// 差分の計算結果に影響する設定から、キャッシュの識別に使用するフィンガープリントを計算します
+    ///
+    /// 設定が1つでも異なる場合は別のフィンガープリントになるため、
+    /// 設定を変更すると以前のキャッシュは使用されなくなります。
+    pub(crate) fn fingerprint(&self) -> String {
+        cache::fingerprint(&format!(
+            "include={:?};exclude={:?};repository_rules={:?};scope={:?};pathspecs={:?};rename_threshold={:?};renames_across_scope={};merge_mode={:?};skip_binary={};generated_markers={:?}",
+            self.include_patterns.sources(),
+            self.exclude_patterns.sources(),
+            self.repository_rules.sources(),
+            self.scope_paths,
+            self.pathspecs,
+            self.rename_threshold,
+            self.renames_across_scope,
+            self.merge_mode,
+            self.skip_binary,
+            self.generated_markers.as_ref().map(|regex| regex.as_str()),
+        ))
+    }
+
+    /// 指定されたファイルパスが分析対象に含まれるかどうかを判定します
+    ///
+    /// # 引数
+    ///
+    /// - `file_path`: 判定対象のファイルパス
+    ///
+    /// # 戻り値
+    ///
+    /// ファイルが分析対象に含まれる場合は`true`、それ以外は`false`
+    fn should_include_file(&self, file_path: &str) -> bool {
+        if !self.scope_paths.is_empty()
+            && !self
+                .scope_paths
+                .iter()
+                .any(|scope| is_within_scope(file_path, scope))
+        {
+            return false;
+        }
+
+        if self.exclude_patterns.is_match(file_path) || self.repository_rules.is_excluded(file_path)
+        {
+            return false;
+        }
+
+        self.include_patterns.is_empty() || self.include_patterns.is_match(file_path)
+    }
+
+    /// マージコミットを分析対象に含めるかどうかを返します
+    pub(crate) fn includes_merges(&self) -> bool {
+        self.merge_mode != MergeMode::Skip
+    }
+
+    /// コミットで変更されたファイルの一覧を取得します
+    ///
+    /// マージコミットの場合は`merge_mode`に従って各親との差分を組み合わせます。
+    /// 複数の親との差分に含まれるファイルの行数は、第1親に近い親との差分の値を使用します。
+    /// バイナリや自動生成のため除外したファイルは、いずれかの親との差分に含まれれば記録します。
+    pub(crate) fn changed_files(
+        &self,
+        repo: &Repository,
+        commit: &Commit,
+    ) -> Result<ChangedFiles, AnalyzerError> {
+        let tree = commit.tree()?;
+        if commit.parent_count() <= 1 || self.merge_mode == MergeMode::FirstParent {
+            let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+            return self.diff_trees(repo, parent_tree.as_ref(), &tree);
+        }
+
+        let mut per_parent = Vec::new();
+        for parent in commit.parents() {
+            per_parent.push(self.diff_trees(repo, Some(&parent.tree()?), &tree)?);
+        }
+
+        let mut changed = ChangedFiles::default();
+        for change in per_parent.iter().flat_map(|changes| &changes.files) {
+            if changed.files.iter().any(|file| file.path == change.path) {
+                continue;
+            }
+            let changed_from_every_parent = per_parent
+                .iter()
+                .all(|changes| changes.files.iter().any(|other| other.path == change.path));
+            if self.merge_mode == MergeMode::AllParents || changed_from_every_parent {
+                changed.files.push(change.clone());
+            }
+        }
+        for skipped in per_parent.iter().flat_map(|changes| &changes.skipped) {
+            if !changed.skipped.contains(skipped) {
+                changed.skipped.push(skipped.clone());
+            }
+        }
+
+        Ok(changed)
+    }
+
+    /// 2つのツリーの差分から分析対象ファイルの変更を取得します
+    ///
+    /// `rename_threshold`が設定されている場合はリネーム・コピー検出を行い、
+    /// リネームされたファイルには変更前のパスを記録します。
+    /// `renames_across_scope`が有効な場合、分析対象の外へリネームされたファイルは、
+    /// 変更前のパスの削除として記録します。
+    /// 行数の集計や自動生成の判定はコストが高いため、分析対象のファイルに対してのみ行います。
+    fn diff_trees(
+        &self,
+        repo: &Repository,
+        parent_tree: Option<&Tree>,
+        tree: &Tree,
+    ) -> Result<ChangedFiles, AnalyzerError> {
+        let mut diff_options = DiffOptions::new();
+        for pathspec in &self.pathspecs {
+            diff_options.pathspec(pathspec);
+        }
+
+        let mut diff = repo.diff_tree_to_tree(parent_tree, Some(tree), Some(&mut diff_options))?;
+
+        if let Some(threshold) = self.rename_threshold {
+            let mut find_options = DiffFindOptions::new();
+            find_options
+                .renames(true)
+                .copies(true)
+                .rename_threshold(threshold)
+                .copy_threshold(threshold);
+
+            // パススペックで絞り込んだ差分では、絞り込みの範囲の外との間で移動したファイルが
+            // 追加や削除として現れる。範囲をまたぐ移動を検出する場合に限り、追加・削除があれば
+            // 絞り込まずに差分を計算し直す。無関係なファイルの多いコミットで時間がかからないよう、
+            // リネームの候補の数は`CROSS_SCOPE_RENAME_LIMIT`までとする
+            if self.renames_across_scope
+                && !self.pathspecs.is_empty()
+                && diff
+                    .deltas()
+                    .any(|delta| matches!(delta.status(), Delta::Added | Delta::Deleted))
+            {
+                diff = repo.diff_tree_to_tree(parent_tree, Some(tree), None)?;
+                find_options.rename_limit(CROSS_SCOPE_RENAME_LIMIT);
+            }
+
+            diff.find_similar(Some(&mut find_options))?;
+        }
+
+        let mut changed = ChangedFiles::default();
+        for (index, delta) in diff.deltas().enumerate() {
+            // 削除されたファイルは変更前のパスで記録する
+            let deleted = delta.status() == Delta::Deleted;
+            let file = if deleted {
+                delta.old_file()
+            } else {
+                delta.new_file()
+            };
+            let Some(path) = file.path().and_then(|p| p.to_str()) else {
+                continue;
+            };
+
+            if !self.should_include_file(path) {
+                let moved_out = (delta.status() == Delta::Renamed)
+                    .then(|| delta.old_file().path().and_then(|p| p.to_str()))
+                    .flatten()
+                    .filter(|old_path| self.should_include_file(old_path));
+                if let Some(old_path) = moved_out {
+                    changed.files.push(FileChange {
+                        path: old_path.to_string(),
+                        old_path: None,
+                        deleted: true,
+                        lines_added: 0,
+                        lines_deleted: 0,
+                    });
+                }
+                continue;
+            }
+
+            let old_path = match delta.status() {
+                Delta::Renamed => delta
+                    .old_file()
+                    .path()
+                    .and_then(|p| p.to_str())
+                    .map(|p| p.to_string()),
+                _ => None,
+            };
+
+            // バイナリかどうかはパッチの生成時にファイルの内容から判定される
+            let patch = Patch::from_diff(&diff, index)?;
+            let binary = patch.as_ref().map_or(delta.flags().is_binary(), |patch| {
+                patch.delta().flags().is_binary()
+            });
+            let skipped_kind = if self.skip_binary && binary {
+                Some(SkippedFileKind::Binary)
+            } else if !binary && self.is_generated(repo, file.id())? {
+                Some(SkippedFileKind::Generated)
+            } else {
+                None
+            };
+            if let Some(kind) = skipped_kind {
+                changed.skipped.push(SkippedFile {
+                    path: path.to_string(),
+                    kind,
+                });
+                continue;
+            }
+
+            // パッチを生成できない場合は行数を0とする
+            let (lines_added, lines_deleted) = match patch {
+                Some(patch) => {
+                    let (_, additions, deletions) = patch.line_stats()?;
+                    (additions as u32, deletions as u32)
+                }
+                None => (0, 0),
+            };
+
+            changed.files.push(FileChange {
+                path: path.to_string(),
+                old_path,
+                deleted,
+                lines_added,
+                lines_deleted,
+            });
+        }
+
+        Ok(changed)
+    }
+
+    /// ファイルの先頭に自動生成の目印が含まれるかどうかを判定します
+    ///
+    /// `generated_markers`が設定されていない場合は常に`false`を返します。
+    fn is_generated(&self, repo: &Repository, id: git2::Oid) -> Result<bool, AnalyzerError> {
+        let Some(markers) = &self.generated_markers else {
+            return Ok(false);
+        };
+        let blob = repo.find_blob(id)?;
+        let content = blob.content();
+        let header = &content[..content.len().min(GENERATED_HEADER_BYTES)];
+        Ok(markers.is_match(&String::from_utf8_lossy(header)))
+    }
+}
+
+/// 分析対象のパス指定を正規化します（先頭の`./`と末尾の`/`を取り除きます）
+fn normalize_scope_path(path: &str) -> String {
+    let path = path.trim();
+    let path = path.strip_prefix("./").unwrap_or(path);
+    path.trim_end_matches('/').to_string()
+}
+
+/// ファイルパスが分析対象のディレクトリまたはファイルに含まれるかどうかを判定します
+fn is_within_scope(file_path: &str, scope: &str) -> bool {
+    file_path == scope
+        || (file_path.starts_with(scope) && file_path[scope.len()..].starts_with('/'))
+}
+
+/// インクルードパターンを差分計算用のパススペックに変換します
+///
+/// パススペックはパターンによる判定の前に候補を絞り込むためだけに使用するため、
+/// 元のパターンに一致する全てのパスに一致するパススペックを生成します。
+/// libgit2のパススペックでは`*`が`/`にも一致するため、`**/`は`*`に置き換え、
+/// `{a,b}`は展開します。同じ範囲を表せないパターンが1つでもある場合は
+/// 絞り込みを行わず、空のリストを返します。
+fn include_pathspecs(patterns: &[String]) -> Vec<String> {
+    let mut pathspecs = Vec::new();
+
+    for pattern in patterns.iter().flat_map(|pattern| expand_braces(pattern)) {
+        // 否定パターンやエスケープはパススペックで同じ意味を持たない
+        if pattern.starts_with('!') || pattern.contains('\\') {
+            return Vec::new();
+        }
+
+        let trimmed = pattern.trim_end_matches('/');
+        let anchored = trimmed.contains('/');
+        let body = trimmed
+            .strip_prefix('/')
+            .unwrap_or(trimmed)
+            .replace("**/", "*")
+            .replace("**", "*");
+        if body.is_empty() {
+            return Vec::new();
+        }
+
+        // パターンに一致したディレクトリの下のファイルにも一致させる
+        pathspecs.push(body.clone());
+        pathspecs.push(format!("{}/*", body));
+        if !anchored {
+            pathspecs.push(format!("*/{}", body));
+            pathspecs.push(format!("*/{}/*", body));
+        }
+    }
+
+    pathspecs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // パターンを直接指定したChangeExtractorを作成するテスト用ヘルパー関数
+    fn test_extractor(include_patterns: &[&str], exclude_patterns: &[&str]) -> ChangeExtractor {
+        let to_globs = |patterns: &[&str]| {
+            let patterns: Vec<String> = patterns.iter().map(|p| p.to_string()).collect();
+            GlobSet::new(&patterns).unwrap()
+        };
+        ChangeExtractor {
+            include_patterns: to_globs(include_patterns),
+            exclude_patterns: to_globs(exclude_patterns),
+            repository_rules: RepositoryRules::default(),
+            scope_paths: Vec::new(),
+            pathspecs: Vec::new(),
+            rename_threshold: None,
+            renames_across_scope: false,
+            merge_mode: MergeMode::Skip,
+            skip_binary: true,
+            generated_markers: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_mode_from_str() {
+        assert_eq!("skip".parse::<MergeMode>().unwrap(), MergeMode::Skip);
+        assert_eq!(
+            "first-parent".parse::<MergeMode>().unwrap(),
+            MergeMode::FirstParent
+        );
+        assert_eq!(
+            "all-parents".parse::<MergeMode>().unwrap(),
+            MergeMode::AllParents
+        );
+        assert_eq!(
+            "combined".parse::<MergeMode>().unwrap(),
+            MergeMode::Combined
+        );
+        assert!("octopus".parse::<MergeMode>().is_err());
+    }
+
+    #[test]
+    fn test_should_include_file() {
+        let extractor = test_extractor(&["*.rs", "src/**/*.toml"], &["/target/"]);
+
+        assert!(extractor.should_include_file("src/main.rs"));
+        assert!(extractor.should_include_file("src/config.toml"));
+        assert!(!extractor.should_include_file("src/main.py"));
+        assert!(!extractor.should_include_file("target/debug/main.rs"));
+    }
+
+    #[test]
+    fn test_should_include_file_edge_cases() {
+        let extractor = test_extractor(&["*.{rs,toml}", "src/**"], &["target/", "*.generated.*"]);
+
+        // 境界ケースのテスト
+        assert!(extractor.should_include_file("src/")); // ディレクトリパス
+        assert!(extractor.should_include_file("src/module/file.rs")); // ネストされたパス
+        assert!(extractor.should_include_file("config.toml")); // ルートのtomlファイル
+        assert!(!extractor.should_include_file("")); // 空のパス
+        assert!(!extractor.should_include_file("target/debug/file.rs")); // 除外ディレクトリ
+    }
+
+    #[test]
+    fn test_should_include_file_with_empty_patterns() {
+        let extractor = test_extractor(&[], &[]);
+
+        // 空のパターンの場合、全てのファイルが含まれる
+        assert!(extractor.should_include_file("any_file.txt"));
+        assert!(extractor.should_include_file("src/main.rs"));
+        assert!(extractor.should_include_file("deeply/nested/path/file.js"));
+    }
+
+    #[test]
+    fn test_scope_paths() {
+        assert_eq!(normalize_scope_path("./src/core/"), "src/core");
+        assert_eq!(normalize_scope_path("src"), "src");
+
+        assert!(is_within_scope("src/core/lib.rs", "src/core"));
+        assert!(is_within_scope("src/core", "src/core"));
+        assert!(!is_within_scope("src/core_utils/lib.rs", "src/core"));
+        assert!(!is_within_scope("lib/src/core/lib.rs", "src/core"));
+    }
+
+    #[test]
+    fn test_should_include_file_with_negation() {
+        let extractor = test_extractor(&["src/**", "!src/generated/"], &["*.snap", "!keep.snap"]);
+
+        assert!(extractor.should_include_file("src/lib.rs"));
+        assert!(!extractor.should_include_file("src/generated/api.rs"));
+        assert!(!extractor.should_include_file("src/ui/view.snap"));
+        assert!(extractor.should_include_file("src/ui/keep.snap"));
+    }
+
+    #[test]
+    fn test_include_pathspecs() {
+        let to_strings =
+            |patterns: &[&str]| -> Vec<String> { patterns.iter().map(|p| p.to_string()).collect() };
+
+        assert_eq!(
+            include_pathspecs(&to_strings(&["src/**/*.{ts,tsx}"])),
+            to_strings(&["src/*.ts", "src/*.ts/*", "src/*.tsx", "src/*.tsx/*"])
+        );
+        assert_eq!(
+            include_pathspecs(&to_strings(&["**/*.rs"])),
+            to_strings(&["*.rs", "*.rs/*"])
+        );
+        assert_eq!(
+            include_pathspecs(&to_strings(&["[Mm]akefile"])),
+            to_strings(&[
+                "[Mm]akefile",
+                "[Mm]akefile/*",
+                "*/[Mm]akefile",
+                "*/[Mm]akefile/*"
+            ])
+        );
+
+        // パススペックで同じ範囲を表せないパターンがある場合は絞り込まない
+        assert!(include_pathspecs(&to_strings(&["**/*.rs", "!main.rs"])).is_empty());
+        assert!(include_pathspecs(&to_strings(&["\\*.rs"])).is_empty());
+    }
+}
@@ -12,6 +12,8 @@ use thiserror::Error;
 /// - `InvalidRepository` - 無効なGitリポジトリパスが指定された場合のエラー
 /// - `InvalidPattern` - 無効なパターンが指定された場合のエラー
 /// - `AnalysisError` - コード分析プロセス中の一般的なエラー
+/// - `InvalidOption` - 無効なオプション値や設定ファイルが指定された場合のエラー
+/// - `CacheError` - 分析キャッシュの読み書きに関連するエラー
 #[derive(Error, Debug)]
 pub enum AnalyzerError {
     /// Git操作中に発生したエラー
@@ -26,6 +28,14 @@ pub enum AnalyzerError {
     #[error("Invalid pattern: {0}")]
     InvalidPattern(String),
 
+    /// 無効なオプション値や設定ファイルが指定された場合のエラー
+    #[error("Invalid option: {0}")]
+    InvalidOption(String),
+
+    /// 分析キャッシュの読み書き中に発生したエラー
+    #[error("Cache error: {0}")]
+    CacheError(String),
+
     /// hotspot分析プロセス中に発生した一般的なエラー
     #[error("Analysis error: {0}")]
     AnalysisError(String),
@@ -58,6 +68,14 @@ mod tests {
         let error = AnalyzerError::InvalidPattern("invalid regex".to_string());
         assert_eq!(error.to_string(), "Invalid pattern: invalid regex");
 
+        // InvalidOptionのテスト
+        let error = AnalyzerError::InvalidOption("unknown key".to_string());
+        assert_eq!(error.to_string(), "Invalid option: unknown key");
+
+        // CacheErrorのテスト
+        let error = AnalyzerError::CacheError("write failed".to_string());
+        assert_eq!(error.to_string(), "Cache error: write failed");
+
         // AnalysisErrorのテスト
         let error = AnalyzerError::AnalysisError("analysis failed".to_string());
         assert_eq!(error.to_string(), "Analysis error: analysis failed");
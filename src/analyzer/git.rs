@@ -3,38 +3,139 @@
 //! このモジュールは、libgit2を使用してGitリポジトリからコミット履歴を取得し、
 //! ファイルの変更履歴を追跡するための機能を提供します。
 
+use super::author::{parse_trailers, AuthorFilter, AuthorResolver};
+use super::cache::CommitCache;
+use super::complexity::CodeComplexity;
+use super::diff::{ChangeExtractor, FileChange, SkippedFile, SkippedFileKind};
 use super::error::AnalyzerError;
+use super::ignore::RepositoryRules;
+use super::metrics::Diagnostics;
+use super::options::AnalyzerOptions;
+use super::workspace::{discover_packages, Package};
 use chrono::{DateTime, Utc};
-use git2::{Commit, Repository};
+use git2::{Oid, Repository, RevparseMode};
 use regex::Regex;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Gitリポジトリへのアクセスを管理する構造体
 ///
 /// # フィールド
 ///
 /// - `repo`: libgit2のリポジトリハンドル
-/// - `include_patterns`: 分析対象とするファイルパターン
-/// - `exclude_patterns`: 分析から除外するファイルパターン
-/// - `include_merge_commits`: マージコミットを含めるかどうかのフラグ
+/// - `extractor`: 差分から分析対象ファイルの変更を抽出するインスタンス
+/// - `first_parent`: 履歴を第1親のみたどるかどうか
+/// - `author_resolver`: コミット作成者を開発者名に解決するインスタンス
+/// - `author_filter`: 分析に含める開発者を判定するインスタンス
+/// - `coauthor_trailers`: 共同作成者として扱うトレーラー
+/// - `revisions`: 分析対象のリビジョン。空の場合は`HEAD`
+/// - `until`: 分析対象のツリーを選ぶ基準日時（`--until`または`--as-of`）
+/// - `all_branches`: 全てのローカルブランチを分析対象とするかどうか
+/// - `jobs`: 差分の計算に使用するスレッド数
+/// - `cache_dir`: コミットごとの差分の計算結果を保存するディレクトリ。`None`の場合はキャッシュしない
+/// - `max_files_per_commit`: 分析対象とするコミットが変更できるファイル数の上限
+/// - `exclude_messages`: 分析から除外するコミットメッセージのパターン
 pub struct GitRepository {
     repo: Repository,
-    include_patterns: Vec<Regex>,
-    exclude_patterns: Vec<Regex>,
-    include_merge_commits: bool,
+    extractor: ChangeExtractor,
+    first_parent: bool,
+    author_resolver: AuthorResolver,
+    author_filter: AuthorFilter,
+    coauthor_trailers: Vec<String>,
+    revisions: Vec<String>,
+    until: Option<DateTime<Utc>>,
+    all_branches: bool,
+    jobs: usize,
+    cache_dir: Option<PathBuf>,
+    max_files_per_commit: Option<usize>,
+    exclude_messages: Vec<Regex>,
 }
 
+/// 分析期間より古いコミットが何件連続したら履歴の走査を打ち切るか
+///
+/// コミット日時が前後している履歴でも取りこぼさないよう、`git log --since`と同様に
+/// 数件の猶予を設けます。
+const OLD_COMMIT_SLOP: usize = 5;
+
 /// コミット情報を保持する構造体
 ///
 /// # フィールド
 ///
-/// - `author`: コミット作成者の名前
+/// - `id`: コミットのハッシュ
+/// - `author`: コミット作成者の名前（`.mailmap`や別名で正規化済み）
+/// - `co_authors`: トレーラーで指定された共同作成者の名前（作成者本人と重複は除く）
+/// - `timestamp`: コミット日時
+/// - `message`: コミットメッセージ
 /// - `files`: コミットで変更されたファイルのリスト
-/// - `timestamp`: コミットのタイムスタンプ（分析時の時間フィルタリングに使用）
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct CommitInfo {
+    pub id: String,
+    pub author: String,
+    pub co_authors: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+    pub message: String,
+    pub files: Vec<FileChange>,
+}
+
+/// コミット履歴の取得結果を保持する構造体
+///
+/// # フィールド
+///
+/// - `commits`: 分析対象のコミット情報
+/// - `skipped`: コミットのフィルタによって分析から除外されたコミット
+/// - `diagnostics`: バイナリや自動生成のため除外したファイルの集計
+#[derive(Debug, Default)]
+pub struct CommitHistory {
+    pub commits: Vec<CommitInfo>,
+    pub skipped: Vec<SkippedCommit>,
+    pub diagnostics: Diagnostics,
+}
+
+/// 分析から除外されたコミットを表す構造体
+///
+/// # フィールド
+///
+/// - `id`: コミットのハッシュ
+/// - `author`: コミット作成者の名前
+/// - `timestamp`: コミット日時
+/// - `summary`: コミットメッセージの1行目
+/// - `reason`: 除外された理由
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SkippedCommit {
+    pub id: String,
     pub author: String,
-    pub files: Vec<String>,
+    pub timestamp: DateTime<Utc>,
+    pub summary: String,
+    pub reason: SkipReason,
+}
+
+/// コミットが分析から除外された理由を表す列挙型
+///
+/// - `ExcludedAuthor`: 作成者がボットや除外対象の開発者、またはインクルード対象外の開発者
+/// - `TooManyFiles`: 分析対象のファイルを上限より多く変更している
+/// - `ExcludedMessage`: コミットメッセージが除外パターンに一致した
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SkipReason {
+    ExcludedAuthor { author: String },
+    TooManyFiles { files: usize, limit: usize },
+    ExcludedMessage { pattern: String },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ExcludedAuthor { author } => write!(f, "author {} is excluded", author),
+            Self::TooManyFiles { files, limit } => {
+                write!(f, "changes {} files (limit {})", files, limit)
+            }
+            Self::ExcludedMessage { pattern } => write!(f, "message matches {}", pattern),
+        }
+    }
 }
 
 impl GitRepository {
@@ -43,79 +144,137 @@ impl GitRepository {
     /// # 引数
     ///
     /// - `path`: Gitリポジトリのパス
-    /// - `include_patterns`: 分析対象とするファイルパターン
-    /// - `exclude_patterns`: 分析から除外するファイルパターン
-    /// - `include_merge_commits`: マージコミットを含めるかどうか
+    /// - `options`: パターンやマージコミットの扱いなどの分析オプション
     ///
     /// # エラー
     ///
     /// 以下の場合にエラーを返します：
     /// - リポジトリのオープンに失敗
-    /// - パターンの正規表現への変換に失敗
-    pub fn open(
-        path: impl AsRef<Path>,
-        include_patterns: Vec<String>,
-        exclude_patterns: Vec<String>,
-        include_merge_commits: bool,
-    ) -> Result<Self, AnalyzerError> {
+    /// - `.gitignore`などのリポジトリの除外ルールの読み込みに失敗
+    /// - パターンやコミットメッセージの除外パターンの正規表現への変換に失敗
+    pub fn open(path: impl AsRef<Path>, options: &AnalyzerOptions) -> Result<Self, AnalyzerError> {
         let repo = Repository::open(path)?;
 
-        let include_patterns = include_patterns
-            .into_iter()
-            .map(|p| Regex::new(&glob_to_regex(&p)))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| AnalyzerError::InvalidPattern(e.to_string()))?;
+        let mailmap = if options.use_mailmap {
+            Some(repo.mailmap()?)
+        } else {
+            None
+        };
+        let author_resolver =
+            AuthorResolver::new(mailmap, options.author_key, options.author_aliases.clone());
 
-        let exclude_patterns = exclude_patterns
-            .into_iter()
-            .map(|p| Regex::new(&glob_to_regex(&p)))
-            .collect::<Result<Vec<_>, _>>()
-            .map_err(|e| AnalyzerError::InvalidPattern(e.to_string()))?;
+        let cache_dir = match (&options.cache_dir, options.cache) {
+            (Some(dir), _) => Some(dir.clone()),
+            (None, true) => Some(repo.path().join("hotspot-cache")),
+            (None, false) => None,
+        };
+
+        // 存在しないリビジョンは履歴の取得時にエラーとして報告する
+        let repository_rules = {
+            let tree = analyzed_tree(&repo, &options.revisions, options.until.or(options.as_of))
+                .ok()
+                .flatten();
+            RepositoryRules::load(&repo, tree.as_ref(), options)?
+        };
 
         Ok(Self {
+            extractor: ChangeExtractor::new(options, repository_rules)?,
             repo,
-            include_patterns,
-            exclude_patterns,
-            include_merge_commits,
+            first_parent: options.first_parent,
+            author_resolver,
+            author_filter: AuthorFilter::new(
+                &options.include_authors,
+                &options.exclude_authors,
+                options.exclude_bots,
+            )?,
+            coauthor_trailers: options.coauthor_trailers.clone(),
+            revisions: options.revisions.clone(),
+            until: options.until.or(options.as_of),
+            all_branches: options.all_branches,
+            jobs: resolve_jobs(options.jobs),
+            cache_dir,
+            max_files_per_commit: options.max_files_per_commit,
+            exclude_messages: options
+                .exclude_messages
+                .iter()
+                .map(|pattern| Regex::new(pattern))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AnalyzerError::InvalidPattern(e.to_string()))?,
         })
     }
 
-    /// 指定されたファイルパスが分析対象に含まれるかどうかを判定します
+    /// 分析対象のリビジョンのツリーから、ワークスペースを構成するパッケージを検出します
     ///
-    /// # 引数
+    /// コミットが1つも無いリポジトリでは空のリストを返します。
     ///
-    /// - `file_path`: 判定対象のファイルパス
-    ///
-    /// # 戻り値
+    /// # エラー
     ///
-    /// ファイルが分析対象に含まれる場合は`true`、それ以外は`false`
-    fn should_include_file(&self, file_path: &str) -> bool {
-        if self
-            .exclude_patterns
-            .iter()
-            .any(|pattern| pattern.is_match(file_path))
-        {
-            return false;
+    /// リビジョンの解決やツリーの読み込みに失敗した場合にエラーを返します
+    pub(crate) fn packages(&self) -> Result<Vec<Package>, AnalyzerError> {
+        match self.analyzed_tree()? {
+            Some(tree) => discover_packages(&self.repo, &tree),
+            None => Ok(Vec::new()),
         }
+    }
 
-        if self.include_patterns.is_empty() {
-            return true;
+    /// 分析対象のリビジョンにおける各ファイルの内容から、規模と複雑さを計算します
+    ///
+    /// リビジョンに存在しないファイルと、バイナリのファイルは結果に含めません。
+    ///
+    /// # エラー
+    ///
+    /// リビジョンの解決やツリー、ファイルの内容の読み込みに失敗した場合にエラーを返します
+    pub(crate) fn code_complexity<'a>(
+        &self,
+        paths: impl IntoIterator<Item = &'a str>,
+    ) -> Result<HashMap<String, CodeComplexity>, AnalyzerError> {
+        let Some(tree) = self.analyzed_tree()? else {
+            return Ok(HashMap::new());
+        };
+
+        let mut complexity = HashMap::new();
+        for path in paths {
+            let Ok(entry) = tree.get_path(Path::new(path)) else {
+                continue;
+            };
+            if entry.kind() != Some(git2::ObjectType::Blob) {
+                continue;
+            }
+            let blob = self.repo.find_blob(entry.id())?;
+            if blob.is_binary() {
+                continue;
+            }
+            let content = String::from_utf8_lossy(blob.content());
+            complexity.insert(path.to_string(), CodeComplexity::measure(path, &content));
         }
+        Ok(complexity)
+    }
 
-        self.include_patterns
-            .iter()
-            .any(|pattern| pattern.is_match(file_path))
+    /// 分析対象のリビジョンのツリーを返します
+    ///
+    /// コミットが1つも無いリポジトリでは`None`を返します。
+    fn analyzed_tree(&self) -> Result<Option<git2::Tree<'_>>, AnalyzerError> {
+        analyzed_tree(&self.repo, &self.revisions, self.until)
     }
 
-    /// 指定された日時以降のコミット情報を取得します
+    /// 指定された期間のコミット情報と、フィルタによって除外されたコミットを取得します
+    ///
+    /// 履歴は新しい順に走査し、分析期間より古いコミットが続いた時点で打ち切ります。
+    /// 返されるコミットは、子コミットが必ず親コミットより先に現れる順序に並びます。
+    /// キャッシュが有効な場合は、キャッシュに無いコミットの差分だけを計算します。
+    ///
+    /// コミットメッセージが除外パターンに一致するコミットと、分析対象のファイルを
+    /// `max_files_per_commit`より多く変更しているコミットは除外され、理由とともに記録されます。
+    /// 分析対象のコミットでバイナリや自動生成のため除外したファイルは、パスごとに1件として集計します。
     ///
     /// # 引数
     ///
     /// - `since`: この日時以降のコミットを取得
+    /// - `until`: この日時以前のコミットを取得
     ///
     /// # 戻り値
     ///
-    /// コミット情報のベクターを返します
+    /// 分析対象のコミット情報と除外されたコミットを返します
     ///
     /// # エラー
     ///
@@ -125,12 +284,139 @@ impl GitRepository {
     pub fn get_commits_since(
         &self,
         since: DateTime<Utc>,
-    ) -> Result<Vec<CommitInfo>, AnalyzerError> {
+        until: DateTime<Utc>,
+    ) -> Result<CommitHistory, AnalyzerError> {
+        let oids = self.walk_commits(since, until)?;
+
+        let extracted = match &self.cache_dir {
+            Some(cache_dir) => self.extract_commits_with_cache(&oids, cache_dir)?,
+            None => self.extract_commits(&oids)?,
+        };
+
+        let mut history = CommitHistory::default();
+        let mut skipped_files: HashSet<SkippedFile> = HashSet::new();
+        for (oid, extracted) in oids.iter().zip(extracted) {
+            if extracted.files.is_empty() && extracted.skipped_files.is_empty() {
+                continue;
+            }
+
+            let skip_reason = self.skip_reason(&extracted);
+            if skip_reason.is_none() {
+                skipped_files.extend(extracted.skipped_files.iter().cloned());
+            }
+            if extracted.files.is_empty() {
+                continue;
+            }
+
+            let summary = extracted.message.lines().next().unwrap_or("").to_string();
+            let commit = self.to_commit_info(*oid, extracted);
+            match skip_reason {
+                Some(reason) => {
+                    history.skipped.push(SkippedCommit {
+                        id: commit.id,
+                        author: commit.author,
+                        timestamp: commit.timestamp,
+                        summary,
+                        reason,
+                    });
+                }
+                None => history.commits.push(commit),
+            }
+        }
+
+        for skipped in &skipped_files {
+            match skipped.kind {
+                SkippedFileKind::Binary => history.diagnostics.binary_files += 1,
+                SkippedFileKind::Generated => history.diagnostics.generated_files += 1,
+            }
+        }
+        Ok(history)
+    }
+
+    /// コミットを分析から除外する理由を返します。除外しない場合は`None`を返します
+    fn skip_reason(&self, extracted: &ExtractedCommit) -> Option<SkipReason> {
+        let author = self
+            .author_resolver
+            .resolve(&extracted.author_name, &extracted.author_email);
+        if self.author_filter.is_excluded(&[
+            &extracted.author_name,
+            &extracted.author_email,
+            &author,
+        ]) {
+            return Some(SkipReason::ExcludedAuthor { author });
+        }
+
+        if let Some(pattern) = self
+            .exclude_messages
+            .iter()
+            .find(|pattern| pattern.is_match(&extracted.message))
+        {
+            return Some(SkipReason::ExcludedMessage {
+                pattern: pattern.as_str().to_string(),
+            });
+        }
+
+        match self.max_files_per_commit {
+            Some(limit) if extracted.files.len() > limit => Some(SkipReason::TooManyFiles {
+                files: extracted.files.len(),
+                limit,
+            }),
+            _ => None,
+        }
+    }
+
+    /// キャッシュを使用して各コミットの情報を抽出します
+    ///
+    /// キャッシュに無いコミットのみ差分を計算し、その結果をキャッシュに追記します。
+    /// 分析対象のファイルを変更していないコミットも、再計算を避けるためにキャッシュします。
+    fn extract_commits_with_cache(
+        &self,
+        oids: &[Oid],
+        cache_dir: &Path,
+    ) -> Result<Vec<ExtractedCommit>, AnalyzerError> {
+        let fingerprint = self.extractor.fingerprint();
+        let mut cache = CommitCache::load(cache_dir, &fingerprint)?;
+
+        let mut extracted: Vec<Option<ExtractedCommit>> = oids
+            .iter()
+            .map(|oid| cache.get(&oid.to_string()).cloned())
+            .collect();
+        let missing: Vec<usize> = (0..oids.len())
+            .filter(|&index| extracted[index].is_none())
+            .collect();
+        let missing_oids: Vec<Oid> = missing.iter().map(|&index| oids[index]).collect();
+
+        for (index, commit) in missing
+            .into_iter()
+            .zip(self.extract_commits(&missing_oids)?)
+        {
+            cache.insert(oids[index].to_string(), commit.clone());
+            extracted[index] = Some(commit);
+        }
+        cache.save(&fingerprint)?;
+
+        Ok(extracted.into_iter().flatten().collect())
+    }
+
+    /// 分析期間内の対象コミットを走査し、子コミットが親より先になる順序で返します
+    ///
+    /// 走査は差分を計算せずにコミットのメタデータのみを読み込みます。
+    fn walk_commits(
+        &self,
+        since: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<Vec<Oid>, AnalyzerError> {
         let mut revwalk = self.repo.revwalk()?;
-        revwalk.push_head()?;
+        self.push_revisions(&mut revwalk)?;
+        if self.first_parent {
+            revwalk.simplify_first_parent()?;
+        }
+        // トポロジカル順は走査前に履歴全体の読み込みが必要になるため時刻順のみを指定し、
+        // 期間内のコミットだけを後から並べ替える
         revwalk.set_sorting(git2::Sort::TIME)?;
 
-        let mut commits = Vec::new();
+        let mut walked = Vec::new();
+        let mut old_commits = 0;
         for oid in revwalk {
             let oid = oid?;
             let commit = self.repo.find_commit(oid)?;
@@ -140,214 +426,425 @@ impl GitRepository {
                     AnalyzerError::AnalysisError("Invalid commit timestamp".to_string())
                 })?;
 
-            // 指定された日時より前のコミットはスキップ
+            // 分析期間より古いコミットが続いたら走査を打ち切る
             if commit_time < since {
+                old_commits += 1;
+                if old_commits >= OLD_COMMIT_SLOP {
+                    break;
+                }
                 continue;
             }
+            old_commits = 0;
 
-            // マージコミットを除外
-            if !self.include_merge_commits && commit.parent_count() > 1 {
+            // 分析期間より新しいコミットはスキップ
+            if commit_time > until {
                 continue;
             }
 
-            let author = commit.author().name().unwrap_or("unknown").to_string();
+            // マージコミットは並べ替えのために記録するが、分析対象からは除外
+            let included = self.extractor.includes_merges() || commit.parent_count() <= 1;
+            let parents = if self.first_parent {
+                commit.parent_ids().take(1).collect()
+            } else {
+                commit.parent_ids().collect()
+            };
 
-            let files: Vec<String> = self
-                .get_changed_files(&commit)?
-                .into_iter()
-                .filter(|file_path| self.should_include_file(file_path))
-                .collect();
+            walked.push(WalkedCommit {
+                oid,
+                time: commit.time().seconds(),
+                parents,
+                included,
+            });
+        }
 
-            // 変更されたファイルがある場合はコミット情報を追加
-            if !files.is_empty() {
-                commits.push(CommitInfo { author, files });
+        Ok(topological_order(walked)
+            .into_iter()
+            .filter(|commit| commit.included)
+            .map(|commit| commit.oid)
+            .collect())
+    }
+
+    /// 分析期間内にマージされたコミットと、そのコミットを取り込んだマージコミットの対応を返します
+    ///
+    /// 分析対象のリビジョンから第1親をたどった履歴上のマージコミットごとに、
+    /// 第2親以降からのみ到達できるコミットを、そのマージコミットで取り込まれたコミットとします。
+    /// 第1親をたどった履歴上のコミットは対応に含みません。
+    pub(crate) fn merged_commits(
+        &self,
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<String, String>, AnalyzerError> {
+        let mut mainline = self.repo.revwalk()?;
+        self.push_revisions(&mut mainline)?;
+        mainline.simplify_first_parent()?;
+        mainline.set_sorting(git2::Sort::TIME)?;
+
+        let mut merged = HashMap::new();
+        let mut old_commits = 0;
+        for oid in mainline {
+            let merge = self.repo.find_commit(oid?)?;
+            if merge.time().seconds() < since.timestamp() {
+                old_commits += 1;
+                if old_commits >= OLD_COMMIT_SLOP {
+                    break;
+                }
+                continue;
+            }
+            old_commits = 0;
+            if merge.parent_count() <= 1 {
+                continue;
+            }
+
+            let merge_id = merge.id().to_string();
+            let mut branch = self.repo.revwalk()?;
+            for parent in merge.parent_ids().skip(1) {
+                branch.push(parent)?;
+            }
+            branch.hide(merge.parent_id(0)?)?;
+            branch.set_sorting(git2::Sort::TIME)?;
+
+            let mut old_commits = 0;
+            for oid in branch {
+                let oid = oid?;
+                if self.repo.find_commit(oid)?.time().seconds() < since.timestamp() {
+                    old_commits += 1;
+                    if old_commits >= OLD_COMMIT_SLOP {
+                        break;
+                    }
+                    continue;
+                }
+                old_commits = 0;
+                merged
+                    .entry(oid.to_string())
+                    .or_insert_with(|| merge_id.clone());
             }
         }
+        Ok(merged)
+    }
+
+    /// 各コミットの差分を計算し、分析に使用する情報を抽出します
+    ///
+    /// `jobs`が2以上の場合は、スレッドごとにリポジトリハンドルを開いて並列に差分を計算します。
+    /// 結果は`oids`と同じ順序で返すため、スレッド数によらず同じ結果になります。
+    fn extract_commits(&self, oids: &[Oid]) -> Result<Vec<ExtractedCommit>, AnalyzerError> {
+        let jobs = self.jobs.min(oids.len());
+        if jobs <= 1 {
+            return oids
+                .iter()
+                .map(|&oid| extract_commit(&self.repo, &self.extractor, oid))
+                .collect();
+        }
+
+        let repo_path = self.repo.path();
+        let extractor = &self.extractor;
+        let next_index = AtomicUsize::new(0);
+
+        let worker_results = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..jobs)
+                .map(|_| {
+                    scope.spawn(|| -> Result<Vec<_>, AnalyzerError> {
+                        let repo = Repository::open(repo_path)?;
+                        let mut results = Vec::new();
+                        loop {
+                            let index = next_index.fetch_add(1, Ordering::Relaxed);
+                            let Some(&oid) = oids.get(index) else {
+                                break;
+                            };
+                            results.push((index, extract_commit(&repo, extractor, oid)?));
+                        }
+                        Ok(results)
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| {
+                    worker.join().unwrap_or_else(|_| {
+                        Err(AnalyzerError::AnalysisError(
+                            "Diff worker thread panicked".to_string(),
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })?;
 
-        Ok(commits)
+        let mut extracted: Vec<Option<ExtractedCommit>> = oids.iter().map(|_| None).collect();
+        for (index, commit) in worker_results.into_iter().flatten() {
+            extracted[index] = Some(commit);
+        }
+        Ok(extracted.into_iter().flatten().collect())
     }
 
-    fn get_changed_files(&self, commit: &Commit) -> Result<Vec<String>, AnalyzerError> {
-        let tree = commit.tree()?;
-        let parent_tree = commit.parent(0).ok().and_then(|parent| parent.tree().ok());
+    /// 抽出したコミットの作成者と共同作成者を解決し、コミット情報に変換します
+    fn to_commit_info(&self, oid: Oid, extracted: ExtractedCommit) -> CommitInfo {
+        let author = self
+            .author_resolver
+            .resolve(&extracted.author_name, &extracted.author_email);
+        let co_authors = self.get_co_authors(&extracted.message, &author);
+        let timestamp = DateTime::from_timestamp(extracted.timestamp, 0).unwrap_or_default();
 
-        let diff = self
-            .repo
-            .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+        CommitInfo {
+            id: oid.to_string(),
+            author,
+            co_authors,
+            timestamp,
+            message: extracted.message,
+            files: extracted.files,
+        }
+    }
 
-        let mut files = Vec::new();
-        for delta in diff.deltas() {
-            if let Some(path) = delta.new_file().path() {
-                if let Some(path_str) = path.to_str() {
-                    files.push(path_str.to_string());
+    /// 分析対象のリビジョンをrevwalkに追加します
+    ///
+    /// リビジョンはgitのリビジョン構文で指定します。
+    ///
+    /// - `v1.3.0`や`release/1.x`などの単一のリビジョン: そのコミットから到達可能な履歴
+    /// - `v1.2.0..v1.3.0`: 終点から到達可能で、始点からは到達できない履歴
+    /// - `main...feature`: どちらか一方からのみ到達可能な履歴
+    /// - `^v1.2.0`: 指定したコミットから到達可能な履歴を除外
+    ///
+    /// 複数のリビジョンやブランチから到達できるコミットは1度だけ返されます。
+    fn push_revisions(&self, revwalk: &mut git2::Revwalk) -> Result<(), AnalyzerError> {
+        if self.all_branches {
+            revwalk.push_glob("refs/heads/*")?;
+        } else if self.revisions.is_empty() {
+            revwalk.push_head()?;
+        }
+
+        for revision in &self.revisions {
+            if let Some(excluded) = revision.strip_prefix('^') {
+                let commit = self.repo.revparse_single(excluded)?.peel_to_commit()?;
+                revwalk.hide(commit.id())?;
+                continue;
+            }
+
+            let revspec = self.repo.revparse(revision)?;
+            let from = revspec
+                .from()
+                .map(|object| object.peel_to_commit())
+                .transpose()?;
+            let to = revspec
+                .to()
+                .map(|object| object.peel_to_commit())
+                .transpose()?;
+
+            match (from, to) {
+                (Some(from), Some(to)) if revspec.mode().contains(RevparseMode::MERGE_BASE) => {
+                    let merge_base = self.repo.merge_base(from.id(), to.id())?;
+                    revwalk.push(from.id())?;
+                    revwalk.push(to.id())?;
+                    revwalk.hide(merge_base)?;
+                }
+                (Some(from), Some(to)) => {
+                    revwalk.push(to.id())?;
+                    revwalk.hide(from.id())?;
+                }
+                (Some(commit), None) | (None, Some(commit)) => revwalk.push(commit.id())?,
+                (None, None) => {
+                    return Err(AnalyzerError::InvalidOption(format!(
+                        "Invalid revision: {}",
+                        revision
+                    )))
                 }
             }
         }
 
-        Ok(files)
+        Ok(())
     }
-}
 
-fn glob_to_regex(pattern: &str) -> String {
-    let mut regex = String::with_capacity(pattern.len() * 2);
-    regex.push('^');
-
-    let mut chars = pattern.chars().peekable();
-    while let Some(c) = chars.next() {
-        match c {
-            '*' => {
-                let is_double_star = chars.peek() == Some(&'*');
-                if is_double_star {
-                    chars.next(); // Skip second '*'
-                    regex.push_str(if chars.peek() == Some(&'/') {
-                        chars.next();
-                        ".*/"
-                    } else {
-                        ".*"
-                    });
-                } else {
-                    regex.push_str("[^/]*");
-                }
+    /// コミットメッセージのトレーラーから共同作成者を取得します
+    ///
+    /// 共同作成者も作成者と同様に`.mailmap`や別名で正規化し、
+    /// 作成者本人や重複した共同作成者、ボットなど除外対象の開発者は除外します。
+    fn get_co_authors(&self, message: &str, author: &str) -> Vec<String> {
+        if self.coauthor_trailers.is_empty() {
+            return Vec::new();
+        }
+
+        let mut co_authors: Vec<String> = Vec::new();
+        for (name, email) in parse_trailers(message, &self.coauthor_trailers) {
+            let co_author = self.author_resolver.resolve(&name, &email);
+            if self.author_filter.is_excluded(&[&name, &email, &co_author]) {
+                continue;
+            }
+            if co_author != author && !co_authors.contains(&co_author) {
+                co_authors.push(co_author);
             }
-            '?' => regex.push('.'),
-            '.' => regex.push_str("\\."),
-            '/' => regex.push('/'),
-            c if c.is_alphanumeric() => regex.push(c),
-            c => regex.push_str(&regex::escape(&c.to_string())),
         }
+        co_authors
     }
+}
 
-    regex.push('$');
-    regex
+/// 差分の計算結果を保持する構造体
+///
+/// 作成者の名寄せはスレッド間で共有できない`.mailmap`を使用するため、
+/// ここでは名前とメールアドレスをそのまま保持します。
+/// 名寄せの設定に依存しないため、この内容をキャッシュに保存します。
+///
+/// # フィールド
+///
+/// - `author_name`: コミット作成者の名前
+/// - `author_email`: コミット作成者のメールアドレス
+/// - `message`: コミットメッセージ
+/// - `timestamp`: コミット日時（UNIXタイムスタンプ）
+/// - `files`: 分析対象ファイルの変更内容。分析対象のファイルを変更していない場合は空
+/// - `skipped_files`: バイナリや自動生成のため除外したファイル
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ExtractedCommit {
+    author_name: String,
+    author_email: String,
+    message: String,
+    timestamp: i64,
+    files: Vec<FileChange>,
+    #[serde(default)]
+    skipped_files: Vec<SkippedFile>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
+/// コミットの差分を計算し、分析に使用する情報を抽出します
+fn extract_commit(
+    repo: &Repository,
+    extractor: &ChangeExtractor,
+    oid: Oid,
+) -> Result<ExtractedCommit, AnalyzerError> {
+    let commit = repo.find_commit(oid)?;
+    let changed = extractor.changed_files(repo, &commit)?;
 
-    #[test]
-    fn test_glob_to_regex() {
-        let test_cases = [
-            ("*.py", "^[^/]*\\.py$"),
-            ("src/*.rs", "^src/[^/]*\\.rs$"),
-            ("**/*.js", "^.*/[^/]*\\.js$"),
-            ("src/**/*.ts", "^src/.*/[^/]*\\.ts$"),
-            ("doc/*.md", "^doc/[^/]*\\.md$"),
-            ("test/**", "^test/.*$"),
-            ("**.txt", "^.*\\.txt$"),
-        ];
+    let signature = commit.author();
+    Ok(ExtractedCommit {
+        author_name: signature.name().unwrap_or("unknown").to_string(),
+        author_email: signature.email().unwrap_or("").to_string(),
+        message: commit.message().unwrap_or("").to_string(),
+        timestamp: commit.time().seconds(),
+        files: changed.files,
+        skipped_files: changed.skipped,
+    })
+}
 
-        for (input, expected) in test_cases {
-            let result = glob_to_regex(input);
-            assert_eq!(
-                result, expected,
-                "Pattern '{}' should convert to '{}', but got '{}'",
-                input, expected, result
-            );
-        }
+/// スレッド数の指定を実際に使用するスレッド数に変換します
+///
+/// 0の場合は利用可能なCPU数を使用します。
+fn resolve_jobs(jobs: usize) -> usize {
+    if jobs > 0 {
+        return jobs;
     }
-    #[test]
-    fn test_should_include_file() {
-        let repo = Repository::open(".").unwrap();
-        let git_repo = GitRepository {
-            repo,
-            include_patterns: vec![
-                Regex::new("^.*\\.rs$").unwrap(),
-                Regex::new("^src/.*\\.toml$").unwrap(),
-            ],
-            exclude_patterns: vec![Regex::new("^target/.*$").unwrap()],
-            include_merge_commits: false,
-        };
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
 
-        assert!(git_repo.should_include_file("src/main.rs"));
-        assert!(git_repo.should_include_file("src/config.toml"));
-        assert!(!git_repo.should_include_file("src/main.py"));
-        assert!(!git_repo.should_include_file("target/debug/main.rs"));
-    }
+/// 分析対象のリビジョンのツリーを返します
+///
+/// 最初に指定されたリビジョン（範囲指定の場合は終点）のコミットを使用し、
+/// リビジョンが指定されていない場合は`HEAD`を使用します。
+/// `until`が指定されている場合は、そのコミットから第1親をたどり、
+/// コミット日時が`until`以前の最初のコミットのツリーを使用します。
+/// コミットが1つも無いリポジトリや、`until`以前のコミットが無い場合は`None`を返します。
+fn analyzed_tree<'r>(
+    repo: &'r Repository,
+    revisions: &[String],
+    until: Option<DateTime<Utc>>,
+) -> Result<Option<git2::Tree<'r>>, AnalyzerError> {
+    let revision = revisions.iter().find(|revision| !revision.starts_with('^'));
+    let mut commit = match revision {
+        Some(revision) => {
+            let revspec = repo.revparse(revision)?;
+            match revspec.to().or(revspec.from()) {
+                Some(object) => object.peel_to_commit()?,
+                None => {
+                    return Err(AnalyzerError::InvalidOption(format!(
+                        "Invalid revision: {}",
+                        revision
+                    )))
+                }
+            }
+        }
+        None => match repo.head() {
+            Ok(head) => head.peel_to_commit()?,
+            Err(_) => return Ok(None),
+        },
+    };
 
-    #[test]
-    fn test_git_repository_open_invalid_path() {
-        let result = GitRepository::open("non_existent_path", vec![], vec![], false);
-        assert!(result.is_err());
-        match result {
-            Err(AnalyzerError::GitError(_)) => (),
-            _ => panic!("Expected GitError"),
+    if let Some(until) = until {
+        while commit.time().seconds() > until.timestamp() {
+            commit = match commit.parent(0) {
+                Ok(parent) => parent,
+                Err(_) => return Ok(None),
+            };
         }
     }
+    Ok(Some(commit.tree()?))
+}
 
-    #[test]
-    fn test_glob_to_regex_special_cases() {
-        let test_cases = [
-            // 特殊文字を含むパターン
-            ("doc/(a|b).md", "^doc/\\(a\\|b\\)\\.md$"),
-            // 複数のワイルドカードパターン
-            ("**/*.min.*", "^.*/[^/]*\\.min\\.[^/]*$"),
-            // ドット付きパターン
-            (".gitignore", "^\\.gitignore$"),
-            ("*.config.js", "^[^/]*\\.config\\.js$"),
-            // 複雑なネストパターン
-            (
-                "src/**/test/**/*.spec.js",
-                "^src/.*/test/.*/[^/]*\\.spec\\.js$",
-            ),
-        ];
+/// 走査したコミットの並べ替えに使用する情報
+struct WalkedCommit {
+    oid: Oid,
+    time: i64,
+    parents: Vec<Oid>,
+    included: bool,
+}
+
+/// コミットを、子コミットが必ず親コミットより先になるよう並べ替えます
+///
+/// 子コミットが全て出力されたコミットのうち、コミット日時が最も新しいものから順に出力します
+/// （`git log --date-order`と同じ順序）。日時が同じ場合は走査順を維持します。
+fn topological_order(walked: Vec<WalkedCommit>) -> Vec<WalkedCommit> {
+    let index_of: HashMap<Oid, usize> = walked
+        .iter()
+        .enumerate()
+        .map(|(index, commit)| (commit.oid, index))
+        .collect();
 
-        for (input, expected) in test_cases {
-            let result = glob_to_regex(input);
-            assert_eq!(
-                result, expected,
-                "Pattern '{}' should convert to '{}', but got '{}'",
-                input, expected, result
-            );
-
-            // 生成された正規表現が有効であることを確認
-            assert!(
-                Regex::new(&result).is_ok(),
-                "Generated regex '{}' is invalid",
-                result
-            );
+    // 走査したコミットの中での子コミットの数
+    let mut pending_children = vec![0usize; walked.len()];
+    for commit in &walked {
+        for parent in &commit.parents {
+            if let Some(&index) = index_of.get(parent) {
+                pending_children[index] += 1;
+            }
         }
     }
 
-    #[test]
-    fn test_should_include_file_edge_cases() {
-        let repo = Repository::open(".").unwrap();
-        let git_repo = GitRepository {
-            repo,
-            include_patterns: vec![
-                Regex::new("^.*\\.(rs|toml)$").unwrap(),
-                Regex::new("^src/.*$").unwrap(),
-            ],
-            exclude_patterns: vec![
-                Regex::new("^target/.*$").unwrap(),
-                Regex::new("^.*\\.generated\\..*$").unwrap(),
-            ],
-            include_merge_commits: false,
-        };
+    let mut ready: BinaryHeap<(i64, Reverse<usize>)> = walked
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| pending_children[*index] == 0)
+        .map(|(index, commit)| (commit.time, Reverse(index)))
+        .collect();
 
-        // 境界ケースのテスト
-        assert!(git_repo.should_include_file("src/")); // ディレクトリパス
-        assert!(git_repo.should_include_file("src/module/file.rs")); // ネストされたパス
-        assert!(git_repo.should_include_file("config.toml")); // ルートのtomlファイル
-        assert!(!git_repo.should_include_file("")); // 空のパス
-        assert!(!git_repo.should_include_file("target/debug/file.rs")); // 除外ディレクトリ
+    let mut order = Vec::with_capacity(walked.len());
+    while let Some((_, Reverse(index))) = ready.pop() {
+        order.push(index);
+        for parent in &walked[index].parents {
+            if let Some(&parent_index) = index_of.get(parent) {
+                pending_children[parent_index] -= 1;
+                if pending_children[parent_index] == 0 {
+                    ready.push((walked[parent_index].time, Reverse(parent_index)));
+                }
+            }
+        }
     }
 
-    #[test]
-    fn test_git_repository_with_empty_patterns() {
-        let repo = Repository::open(".").unwrap();
-        let git_repo = GitRepository {
-            repo,
-            include_patterns: vec![],
-            exclude_patterns: vec![],
-            include_merge_commits: false,
-        };
+    let mut slots: Vec<Option<WalkedCommit>> = walked.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .filter_map(|index| slots[index].take())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::diff::MergeMode;
+    use super::super::test_utils::{checkout_branch, commit_files, commit_files_at, merge_branch};
+    use super::*;
+    use tempfile::TempDir;
 
-        // 空のパターンの場合、全てのファイルが含まれる
-        assert!(git_repo.should_include_file("any_file.txt"));
-        assert!(git_repo.should_include_file("src/main.rs"));
-        assert!(git_repo.should_include_file("deeply/nested/path/file.js"));
+    #[test]
+    fn test_git_repository_open_invalid_path() {
+        let result = GitRepository::open("non_existent_path", &AnalyzerOptions::default());
+        assert!(result.is_err());
+        match result {
+            Err(AnalyzerError::GitError(_)) => (),
+            _ => panic!("Expected GitError"),
+        }
     }
 
     // テンポラリリポジトリを使用したテスト用ヘルパー関数
@@ -381,14 +878,707 @@ mod tests {
         let (_temp_dir, _repo) = setup_test_repo()?;
 
         // 空のリポジトリでの動作確認
-        let git_repo =
-            GitRepository::open(_temp_dir.path(), vec!["*.rs".to_string()], vec![], false)?;
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.rs".to_string()],
+            ..Default::default()
+        };
+        let git_repo = GitRepository::open(_temp_dir.path(), &options)?;
 
         let since = Utc::now() - chrono::Duration::days(1);
-        let commits = git_repo.get_commits_since(since)?;
+        let commits = git_repo.get_commits_since(since, Utc::now())?.commits;
 
         // 新しいリポジトリなので、コミットは初期コミットのみ
         assert!(commits.is_empty());
         Ok(())
     }
+
+    // main: a.rs → b.rs(v1.0) → c.rs、feature: b.rsから分岐してd.rs
+    fn setup_branched_repo() -> Result<(TempDir, Repository), git2::Error> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+
+        commit_files(&repo, "test", &[("a.rs", Some("a"))], "Add a")?;
+        let tagged = commit_files(&repo, "test", &[("b.rs", Some("b"))], "Add b")?;
+        repo.tag_lightweight("v1.0", &repo.find_object(tagged, None)?, false)?;
+        let main_branch = repo.head()?.shorthand().unwrap().to_string();
+
+        checkout_branch(&repo, "feature")?;
+        commit_files(&repo, "test", &[("d.rs", Some("d"))], "Add d")?;
+
+        checkout_branch(&repo, &main_branch)?;
+        commit_files(&repo, "test", &[("c.rs", Some("c"))], "Add c")?;
+
+        Ok((temp_dir, repo))
+    }
+
+    // 指定したオプションで取得したコミットの変更ファイルを整列して返す
+    fn changed_paths(path: &Path, options: &AnalyzerOptions) -> Vec<String> {
+        let git_repo = GitRepository::open(path, options).unwrap();
+        let since = Utc::now() - chrono::Duration::days(1);
+        let mut paths: Vec<String> = git_repo
+            .get_commits_since(since, Utc::now())
+            .unwrap()
+            .commits
+            .into_iter()
+            .flat_map(|commit| commit.files.into_iter().map(|change| change.path))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_get_commits_for_revisions() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = setup_branched_repo()?;
+        let with_revisions = |revisions: &[&str]| AnalyzerOptions {
+            revisions: revisions.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        };
+
+        // デフォルトはHEADの履歴
+        assert_eq!(
+            changed_paths(temp_dir.path(), &with_revisions(&[])),
+            vec!["a.rs", "b.rs", "c.rs"]
+        );
+        // タグ
+        assert_eq!(
+            changed_paths(temp_dir.path(), &with_revisions(&["v1.0"])),
+            vec!["a.rs", "b.rs"]
+        );
+        // 範囲指定
+        assert_eq!(
+            changed_paths(temp_dir.path(), &with_revisions(&["v1.0..feature"])),
+            vec!["d.rs"]
+        );
+        // 対称差
+        assert_eq!(
+            changed_paths(temp_dir.path(), &with_revisions(&["HEAD...feature"])),
+            vec!["c.rs", "d.rs"]
+        );
+        // 除外指定
+        assert_eq!(
+            changed_paths(temp_dir.path(), &with_revisions(&["feature", "^v1.0"])),
+            vec!["d.rs"]
+        );
+        // 存在しないリビジョン
+        let git_repo = GitRepository::open(temp_dir.path(), &with_revisions(&["no-such-rev"]))?;
+        assert!(git_repo
+            .get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_for_all_branches() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = setup_branched_repo()?;
+        let options = AnalyzerOptions {
+            all_branches: true,
+            ..Default::default()
+        };
+
+        // 共通の祖先コミットは1度だけ数えられる
+        assert_eq!(
+            changed_paths(temp_dir.path(), &options),
+            vec!["a.rs", "b.rs", "c.rs", "d.rs"]
+        );
+
+        Ok(())
+    }
+
+    // main: a.rs, b.rsを追加 → a.rsを変更、feature: b.rsを変更
+    // マージ時にfeatureのb.rsを取り込み、コンフリクトの解消としてm.rsを追加
+    fn setup_merged_repo() -> Result<(TempDir, Repository), git2::Error> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+
+        commit_files(
+            &repo,
+            "test",
+            &[("a.rs", Some("a")), ("b.rs", Some("b"))],
+            "Base",
+        )?;
+        let main_branch = repo.head()?.shorthand().unwrap().to_string();
+
+        checkout_branch(&repo, "feature")?;
+        commit_files(&repo, "test", &[("b.rs", Some("b2"))], "Change b")?;
+
+        checkout_branch(&repo, &main_branch)?;
+        commit_files(&repo, "test", &[("a.rs", Some("a2"))], "Change a")?;
+        merge_branch(
+            &repo,
+            "test",
+            "feature",
+            &[("b.rs", Some("b2")), ("m.rs", Some("m"))],
+            "Merge feature",
+        )?;
+
+        Ok((temp_dir, repo))
+    }
+
+    #[test]
+    fn test_merge_modes() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = setup_merged_repo()?;
+        let merge_paths = |merge_mode: MergeMode| {
+            let options = AnalyzerOptions {
+                merge_mode,
+                revisions: vec![
+                    "HEAD".to_string(),
+                    "^HEAD^1".to_string(),
+                    "^HEAD^2".to_string(),
+                ],
+                ..Default::default()
+            };
+            changed_paths(temp_dir.path(), &options)
+        };
+
+        assert!(merge_paths(MergeMode::Skip).is_empty());
+        assert_eq!(merge_paths(MergeMode::FirstParent), vec!["b.rs", "m.rs"]);
+        assert_eq!(
+            merge_paths(MergeMode::AllParents),
+            vec!["a.rs", "b.rs", "m.rs"]
+        );
+        assert_eq!(merge_paths(MergeMode::Combined), vec!["m.rs"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_parent_traversal() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = setup_merged_repo()?;
+
+        // featureブランチのコミットは第1親の履歴に含まれない
+        let options = AnalyzerOptions {
+            first_parent: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            changed_paths(temp_dir.path(), &options),
+            vec!["a.rs", "a.rs", "b.rs"]
+        );
+
+        // マージコミットで取り込まれた変更をまとめて数える
+        let options = AnalyzerOptions {
+            first_parent: true,
+            merge_mode: MergeMode::FirstParent,
+            ..Default::default()
+        };
+        assert_eq!(
+            changed_paths(temp_dir.path(), &options),
+            vec!["a.rs", "a.rs", "b.rs", "b.rs", "m.rs"]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_merged_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = setup_merged_repo()?;
+        let git_repo = GitRepository::open(temp_dir.path(), &AnalyzerOptions::default())?;
+        let merged = git_repo.merged_commits(Utc::now() - chrono::Duration::days(1))?;
+
+        // featureブランチのコミットだけがマージコミットに対応付けられる
+        let head = repo.head()?.peel_to_commit()?;
+        let feature = head.parent_id(1)?.to_string();
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged.get(&feature), Some(&head.id().to_string()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let oid = |n: u8| Oid::from_bytes(&[n; 20]).unwrap();
+        let walked = |n: u8, time: i64, parents: &[u8]| WalkedCommit {
+            oid: oid(n),
+            time,
+            parents: parents.iter().map(|&p| oid(p)).collect(),
+            included: true,
+        };
+
+        // 同じ日時のコミットや、親より古い日時の子コミットがあっても子が先になる
+        let commits = vec![
+            walked(1, 100, &[]),
+            walked(2, 100, &[1]),
+            walked(4, 50, &[3]),
+            walked(3, 100, &[2]),
+            walked(5, 200, &[9]),
+        ];
+        let order: Vec<Oid> = topological_order(commits)
+            .into_iter()
+            .map(|commit| commit.oid)
+            .collect();
+        assert_eq!(order, vec![oid(5), oid(4), oid(3), oid(2), oid(1)]);
+    }
+
+    #[test]
+    fn test_get_commits_skips_filtered_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        commit_files(&repo, "test", &[("a.rs", Some("a"))], "Add a")?;
+        let bulk: Vec<(String, String)> = (0..5)
+            .map(|i| (format!("gen{}.rs", i), format!("// {}", i)))
+            .collect();
+        let bulk_files: Vec<(&str, Option<&str>)> = bulk
+            .iter()
+            .map(|(path, content)| (path.as_str(), Some(content.as_str())))
+            .collect();
+        commit_files(&repo, "test", &bulk_files, "Vendor generated files")?;
+        commit_files(
+            &repo,
+            "test",
+            &[("a.rs", Some("a2"))],
+            "chore(fmt): Reformat\n\nDetails",
+        )?;
+        // 除外パターンに一致しないファイルだけを数える
+        commit_files(
+            &repo,
+            "test",
+            &[("b.rs", Some("b")), ("README.md", Some("readme"))],
+            "Add b",
+        )?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.rs".to_string()],
+            max_files_per_commit: Some(1),
+            exclude_messages: vec![r"^chore\(fmt\)".to_string()],
+            ..Default::default()
+        };
+        let history = GitRepository::open(temp_dir.path(), &options)?
+            .get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())?;
+
+        let analyzed: Vec<&str> = history
+            .commits
+            .iter()
+            .map(|commit| commit.files[0].path.as_str())
+            .collect();
+        assert_eq!(analyzed, vec!["b.rs", "a.rs"]);
+
+        let skipped: Vec<(&str, &SkipReason)> = history
+            .skipped
+            .iter()
+            .map(|commit| (commit.summary.as_str(), &commit.reason))
+            .collect();
+        assert_eq!(
+            skipped,
+            vec![
+                (
+                    "chore(fmt): Reformat",
+                    &SkipReason::ExcludedMessage {
+                        pattern: r"^chore\(fmt\)".to_string()
+                    }
+                ),
+                (
+                    "Vendor generated files",
+                    &SkipReason::TooManyFiles { files: 5, limit: 1 }
+                ),
+            ]
+        );
+        assert_eq!(history.skipped[0].author, "test");
+
+        // 不正な正規表現はエラー
+        let options = AnalyzerOptions {
+            exclude_messages: vec!["(".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            GitRepository::open(temp_dir.path(), &options),
+            Err(AnalyzerError::InvalidPattern(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_skips_binary_and_generated_files() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        commit_files(
+            &repo,
+            "test",
+            &[
+                ("main.go", Some("package main\n")),
+                ("logo.go", Some("\u{0}\u{1}PNG\u{0}")),
+                (
+                    "api.pb.go",
+                    Some("// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n"),
+                ),
+            ],
+            "Add files",
+        )?;
+        commit_files(
+            &repo,
+            "test",
+            &[("logo.go", Some("\u{0}\u{2}PNG\u{0}"))],
+            "Update logo",
+        )?;
+
+        let since = Utc::now() - chrono::Duration::days(1);
+        let paths = |history: &CommitHistory| -> Vec<String> {
+            let mut paths: Vec<String> = history
+                .commits
+                .iter()
+                .flat_map(|commit| commit.files.iter().map(|file| file.path.clone()))
+                .collect();
+            paths.sort();
+            paths.dedup();
+            paths
+        };
+
+        let options = AnalyzerOptions {
+            skip_generated: true,
+            ..Default::default()
+        };
+        let history =
+            GitRepository::open(temp_dir.path(), &options)?.get_commits_since(since, Utc::now())?;
+        assert_eq!(paths(&history), vec!["main.go"]);
+        // 同じファイルを複数のコミットで除外しても1件と数える
+        assert_eq!(
+            history.diagnostics,
+            Diagnostics {
+                binary_files: 1,
+                generated_files: 1,
+            }
+        );
+
+        let options = AnalyzerOptions {
+            skip_binary: false,
+            ..Default::default()
+        };
+        let history =
+            GitRepository::open(temp_dir.path(), &options)?.get_commits_since(since, Utc::now())?;
+        assert_eq!(paths(&history), vec!["api.pb.go", "logo.go", "main.go"]);
+        assert_eq!(history.diagnostics, Diagnostics::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_reason_display() {
+        let reason = SkipReason::TooManyFiles {
+            files: 240,
+            limit: 100,
+        };
+        assert_eq!(reason.to_string(), "changes 240 files (limit 100)");
+        let reason = SkipReason::ExcludedMessage {
+            pattern: "^chore".to_string(),
+        };
+        assert_eq!(reason.to_string(), "message matches ^chore");
+    }
+
+    #[test]
+    fn test_get_commits_with_path_scope() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        commit_files(
+            &repo,
+            "test",
+            &[
+                ("backend/api.rs", Some("api")),
+                ("frontend/app.rs", Some("app")),
+                ("backend_tools/gen.rs", Some("gen")),
+            ],
+            "Initial",
+        )?;
+        commit_files(
+            &repo,
+            "test",
+            &[("frontend/app.rs", Some("app2"))],
+            "Frontend only",
+        )?;
+
+        let options = AnalyzerOptions {
+            paths: vec!["./backend/".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            changed_paths(temp_dir.path(), &options),
+            vec!["backend/api.rs"]
+        );
+
+        // スコープ外のファイルのみを変更したコミットは含まれない
+        let git_repo = GitRepository::open(temp_dir.path(), &options)?;
+        let commits =
+            git_repo.get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())?;
+        assert_eq!(commits.commits.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_detects_renames_across_path_scope() -> Result<(), Box<dyn std::error::Error>>
+    {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        let content = "fn parse() {}\nfn format() {}\nfn print() {}\n";
+        commit_files(
+            &repo,
+            "test",
+            &[
+                ("legacy/parser.rs", Some(content)),
+                (
+                    "backend/old.rs",
+                    Some(content.replace("parse", "old").as_str()),
+                ),
+            ],
+            "Initial",
+        )?;
+        commit_files(
+            &repo,
+            "test",
+            &[
+                ("legacy/parser.rs", None),
+                ("backend/parser.rs", Some(content)),
+            ],
+            "Move parser into backend",
+        )?;
+        commit_files(
+            &repo,
+            "test",
+            &[
+                ("backend/old.rs", None),
+                (
+                    "archive/old.rs",
+                    Some(content.replace("parse", "old").as_str()),
+                ),
+            ],
+            "Move old out of backend",
+        )?;
+
+        let changes = |renames_across_scope: bool| {
+            let options = AnalyzerOptions {
+                paths: vec!["backend".to_string()],
+                renames_across_scope,
+                ..Default::default()
+            };
+            GitRepository::open(temp_dir.path(), &options)
+                .unwrap()
+                .get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())
+                .unwrap()
+                .commits
+                .into_iter()
+                .map(|commit| {
+                    let file = &commit.files[0];
+                    (
+                        commit.message,
+                        file.path.clone(),
+                        file.old_path.clone(),
+                        file.deleted,
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let change = |message: &str, path: &str, old_path: Option<&str>, deleted: bool| {
+            (
+                message.to_string(),
+                path.to_string(),
+                old_path.map(|p| p.to_string()),
+                deleted,
+            )
+        };
+
+        // デフォルトではスコープの中だけでリネームを検出する
+        assert_eq!(
+            changes(false),
+            vec![
+                change("Move old out of backend", "backend/old.rs", None, true),
+                change("Move parser into backend", "backend/parser.rs", None, false),
+                change("Initial", "backend/old.rs", None, false),
+            ]
+        );
+
+        // スコープの外から移動したファイルはリネームとして記録し、外へ移動したファイルは削除とする
+        assert_eq!(
+            changes(true),
+            vec![
+                change("Move old out of backend", "backend/old.rs", None, true),
+                change(
+                    "Move parser into backend",
+                    "backend/parser.rs",
+                    Some("legacy/parser.rs"),
+                    false
+                ),
+                change("Initial", "backend/old.rs", None, false),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_commits_stops_at_old_commits() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        let old = Utc::now() - chrono::Duration::days(100);
+        for i in 0..10 {
+            let content = format!("old {}", i);
+            commit_files_at(&repo, "test", &[("old.rs", Some(&content))], "Old", old)?;
+        }
+        commit_files(&repo, "test", &[("new.rs", Some("new"))], "New")?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.rs".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(changed_paths(temp_dir.path(), &options), vec!["new.rs"]);
+
+        Ok(())
+    }
+
+    // 複数の開発者が複数のファイルを変更するコミットを`commit_count`件作成します
+    fn setup_large_repo(commit_count: usize) -> Result<(TempDir, Repository), git2::Error> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        let authors = ["alice", "bob", "carol", "dave"];
+
+        for i in 0..commit_count {
+            let contents: Vec<(String, String)> = (0..3)
+                .map(|j| {
+                    let path = format!("src/module{}/file{}.rs", (i + j) % 7, (i * j) % 11);
+                    let body = (0..(i % 20 + 5))
+                        .map(|line| format!("fn f{}_{}() {{}}\n", i, line))
+                        .collect::<String>();
+                    (path, body)
+                })
+                .collect();
+            let files: Vec<(&str, Option<&str>)> = contents
+                .iter()
+                .map(|(path, body)| (path.as_str(), Some(body.as_str())))
+                .collect();
+            commit_files(
+                &repo,
+                authors[i % authors.len()],
+                &files,
+                &format!("Commit {}", i),
+            )?;
+        }
+
+        Ok((temp_dir, repo))
+    }
+
+    fn commits_with_jobs(path: &Path, jobs: usize) -> Vec<CommitInfo> {
+        let options = AnalyzerOptions {
+            jobs,
+            ..Default::default()
+        };
+        GitRepository::open(path, &options)
+            .unwrap()
+            .get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())
+            .unwrap()
+            .commits
+    }
+
+    #[test]
+    fn test_parallel_extraction_matches_sequential() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = setup_large_repo(60)?;
+
+        let sequential = commits_with_jobs(temp_dir.path(), 1);
+        assert_eq!(sequential.len(), 60);
+        for jobs in [2, 4, 16] {
+            assert_eq!(commits_with_jobs(temp_dir.path(), jobs), sequential);
+        }
+
+        Ok(())
+    }
+
+    // キャッシュディレクトリ内の各キャッシュファイルの行数（ヘッダーを含む）を返す
+    fn cache_line_counts(cache_dir: &Path) -> Vec<usize> {
+        let mut counts: Vec<usize> = std::fs::read_dir(cache_dir)
+            .unwrap()
+            .map(|entry| {
+                let content = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+                content.lines().count()
+            })
+            .collect();
+        counts.sort();
+        counts
+    }
+
+    #[test]
+    fn test_cached_extraction_matches_uncached() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = setup_large_repo(10)?;
+        commit_files(&repo, "test", &[("README.md", Some("readme"))], "Docs")?;
+        let cache_dir = TempDir::new().unwrap();
+        let options = AnalyzerOptions {
+            include_patterns: vec!["**/*.rs".to_string()],
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+        let uncached = |options: &AnalyzerOptions| {
+            let options = AnalyzerOptions {
+                cache_dir: None,
+                ..options.clone()
+            };
+            GitRepository::open(temp_dir.path(), &options)
+                .unwrap()
+                .get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())
+                .unwrap()
+                .commits
+        };
+        let cached = |options: &AnalyzerOptions| {
+            GitRepository::open(temp_dir.path(), options)
+                .unwrap()
+                .get_commits_since(Utc::now() - chrono::Duration::days(1), Utc::now())
+                .unwrap()
+                .commits
+        };
+
+        // 対象ファイルを変更していないコミットも含めて全てキャッシュされる
+        assert_eq!(cached(&options), uncached(&options));
+        assert_eq!(cache_line_counts(cache_dir.path()), vec![12]);
+
+        // 2回目以降は新しいコミットのみが追記される
+        assert_eq!(cached(&options), uncached(&options));
+        commit_files(&repo, "test", &[("src/new.rs", Some("new"))], "New")?;
+        assert_eq!(cached(&options), uncached(&options));
+        assert_eq!(cache_line_counts(cache_dir.path()), vec![13]);
+
+        // 差分の計算条件を変更すると別のキャッシュが使用され、以前のキャッシュは削除される
+        let changed = AnalyzerOptions {
+            include_patterns: vec!["**/*.md".to_string()],
+            ..options.clone()
+        };
+        assert_eq!(cached(&changed), uncached(&changed));
+        assert_eq!(cache_line_counts(cache_dir.path()), vec![13]);
+        assert_eq!(cached(&options), uncached(&options));
+        assert_eq!(cache_line_counts(cache_dir.path()), vec![13]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_jobs() {
+        assert_eq!(resolve_jobs(3), 3);
+        assert!(resolve_jobs(0) >= 1);
+    }
+
+    // 大きめのリポジトリで並列化による速度向上を確認します
+    // cargo test --release -- --ignored --nocapture parallel_extraction_speedup
+    #[test]
+    #[ignore]
+    fn test_parallel_extraction_speedup() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = setup_large_repo(2000)?;
+
+        let started = std::time::Instant::now();
+        let sequential = commits_with_jobs(temp_dir.path(), 1);
+        let sequential_time = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let parallel = commits_with_jobs(temp_dir.path(), 4);
+        let parallel_time = started.elapsed();
+
+        println!(
+            "sequential: {:?}, parallel (4 jobs, {} CPUs): {:?}",
+            sequential_time,
+            resolve_jobs(0),
+            parallel_time
+        );
+        assert_eq!(parallel, sequential);
+        // 複数のCPUを使用できる環境では、並列化した方が速い
+        if resolve_jobs(0) > 1 {
+            assert!(parallel_time < sequential_time);
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,447 @@
+//! gitignore形式のglobパターンを扱うモジュール
+//!
+//! このモジュールは、インクルード・除外パターンとして指定されたglobパターンを
+//! `.gitignore`と同じ規則で解釈し、ファイルパスと照合する機能を提供します。
+//!
+//! # 対応する記法
+//!
+//! - `*`: `/`以外の任意の文字列
+//! - `?`: `/`以外の任意の1文字
+//! - `**`: 先頭の`**/`、途中の`/**/`、末尾の`/**`で任意の階層のディレクトリ
+//! - `[abc]`、`[a-z]`、`[!abc]`（`[^abc]`）: 文字クラス
+//! - `{a,b}`: いずれかの文字列（入れ子も可）
+//! - `\`: 直後の文字をそのまま扱う
+//! - 先頭の`!`: 否定パターン（一致したファイルを直前までのパターンの結果から除く）
+//!
+//! # パスとの照合規則
+//!
+//! - 先頭または途中に`/`を含むパターンはリポジトリのルートからのパスと照合し、
+//!   含まないパターンは任意の階層のファイル名・ディレクトリ名と照合します
+//! - ディレクトリに一致したパターンは、そのディレクトリ以下の全てのファイルに一致します
+//! - 末尾が`/`のパターンはディレクトリにのみ一致します
+//! - 複数のパターンに一致する場合は、最後に一致したパターンが優先されます
+
+use super::error::AnalyzerError;
+use regex::Regex;
+
+/// 1つのglobパターンを表す構造体
+///
+/// # フィールド
+///
+/// - `source`: 指定されたパターンの文字列
+/// - `regex`: パターンを変換した正規表現
+/// - `negated`: 否定パターン（`!`で始まる）かどうか
+#[derive(Debug, Clone)]
+struct GlobPattern {
+    source: String,
+    regex: Regex,
+    negated: bool,
+}
+
+/// 複数のglobパターンをまとめて照合する構造体
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GlobSet {
+    patterns: Vec<GlobPattern>,
+}
+
+impl GlobSet {
+    /// globパターンのリストからGlobSetインスタンスを作成します
+    ///
+    /// # エラー
+    ///
+    /// 正規表現への変換に失敗した場合に`InvalidPattern`を返します
+    pub(crate) fn new(patterns: &[String]) -> Result<Self, AnalyzerError> {
+        Self::build(patterns, true)
+    }
+
+    /// パターンに一致するパスそのものとだけ照合するGlobSetインスタンスを作成します
+    ///
+    /// `new`と異なり、ディレクトリに一致したパターンをそのディレクトリ以下のパスには一致させません。
+    /// ワークスペースのメンバーのように、ディレクトリ自体を照合する場合に使用します。
+    ///
+    /// # エラー
+    ///
+    /// `new`と同様の場合にエラーを返します
+    pub(crate) fn new_exact(patterns: &[String]) -> Result<Self, AnalyzerError> {
+        Self::build(patterns, false)
+    }
+
+    /// globパターンのリストを変換し、GlobSetインスタンスを作成します
+    fn build(patterns: &[String], match_contents: bool) -> Result<Self, AnalyzerError> {
+        let patterns = patterns
+            .iter()
+            .map(|source| {
+                let (negated, pattern) = match source.strip_prefix('!') {
+                    Some(pattern) => (true, pattern),
+                    None => (false, source.as_str()),
+                };
+                let regex = Regex::new(&glob_to_regex(pattern, match_contents))
+                    .map_err(|e| AnalyzerError::InvalidPattern(format!("{}: {}", source, e)))?;
+                Ok(GlobPattern {
+                    source: source.clone(),
+                    regex,
+                    negated,
+                })
+            })
+            .collect::<Result<Vec<_>, AnalyzerError>>()?;
+
+        Ok(Self { patterns })
+    }
+
+    /// パターンが1つも無い場合に`true`を返します
+    pub(crate) fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// ファイルパスがパターンに一致するかどうかを判定します
+    ///
+    /// 最後に一致したパターンが否定パターンでなければ`true`を返します。
+    pub(crate) fn is_match(&self, path: &str) -> bool {
+        self.patterns
+            .iter()
+            .rev()
+            .find(|pattern| pattern.regex.is_match(path))
+            .is_some_and(|pattern| !pattern.negated)
+    }
+
+    /// 指定されたパターンの文字列を返します
+    pub(crate) fn sources(&self) -> Vec<&str> {
+        self.patterns
+            .iter()
+            .map(|pattern| pattern.source.as_str())
+            .collect()
+    }
+}
+
+/// globパターン（先頭の`!`を除いたもの）を正規表現に変換します
+///
+/// `match_contents`が`true`の場合は、ディレクトリに一致したパターンをその下の全てのファイルに一致させます。
+fn glob_to_regex(pattern: &str, match_contents: bool) -> String {
+    let dir_only = pattern.ends_with('/') && pattern.len() > 1;
+    let trimmed = pattern.trim_end_matches('/');
+    // 末尾以外に`/`を含むパターンはルートからのパスと照合する
+    let anchored = trimmed.contains('/');
+    let body = trimmed.strip_prefix('/').unwrap_or(trimmed);
+
+    let mut regex = String::with_capacity(body.len() * 2 + 16);
+    regex.push('^');
+    if !anchored {
+        regex.push_str("(?:.*/)?");
+    }
+
+    let chars: Vec<char> = body.chars().collect();
+    translate(&chars, &mut regex);
+
+    // ディレクトリに一致した場合は、その下の全てのファイルに一致させる
+    regex.push_str(match (match_contents, dir_only) {
+        (false, _) => "$",
+        (true, true) => "/.*$",
+        (true, false) => "(?:/.*)?$",
+    });
+    regex
+}
+
+/// globパターンの文字列を正規表現に変換し、`regex`に追加します
+fn translate(chars: &[char], regex: &mut String) {
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_start = i == 0 || chars[i - 1] == '/';
+                let mut end = i;
+                while chars.get(end) == Some(&'*') {
+                    end += 1;
+                }
+                match chars.get(end) {
+                    // `**/`: 0個以上のディレクトリ
+                    Some('/') if at_start => {
+                        regex.push_str("(?:.*/)?");
+                        end += 1;
+                    }
+                    // 末尾の`**`: 任意の階層の全て
+                    None if at_start => regex.push_str(".*"),
+                    // それ以外の連続した`*`は`*`と同じ
+                    _ => regex.push_str("[^/]*"),
+                }
+                i = end;
+                continue;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => match parse_class(chars, i) {
+                Some((class, end)) => {
+                    regex.push_str(&class);
+                    i = end;
+                    continue;
+                }
+                None => regex.push_str("\\["),
+            },
+            '{' => match split_braces(chars, i) {
+                Some((alternatives, end)) => {
+                    regex.push_str("(?:");
+                    for (index, alternative) in alternatives.iter().enumerate() {
+                        if index > 0 {
+                            regex.push('|');
+                        }
+                        translate(alternative, regex);
+                    }
+                    regex.push(')');
+                    i = end;
+                    continue;
+                }
+                None => regex.push_str("\\{"),
+            },
+            '\\' if i + 1 < chars.len() => {
+                i += 1;
+                regex.push_str(&regex::escape(&chars[i].to_string()));
+            }
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+}
+
+/// `start`の位置から始まる文字クラス（`[...]`）を正規表現に変換します
+///
+/// 変換した文字クラスと、クラスの直後の位置を返します。閉じ括弧が無い場合は`None`を返します。
+fn parse_class(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let mut i = start + 1;
+    let negated = matches!(chars.get(i), Some('!') | Some('^'));
+    if negated {
+        i += 1;
+    }
+
+    let mut class = String::from(if negated { "[^/" } else { "[" });
+    let mut first = true;
+    while i < chars.len() {
+        let c = chars[i];
+        if c == ']' && !first {
+            class.push(']');
+            return Some((class, i + 1));
+        }
+        first = false;
+
+        let c = if c == '\\' && i + 1 < chars.len() {
+            i += 1;
+            chars[i]
+        } else {
+            c
+        };
+        // 範囲指定以外の記号は正規表現の文字クラスで特別な意味を持たないようエスケープする
+        if c == '-' && i + 1 < chars.len() && chars[i + 1] != ']' && !class.ends_with(['[', '/']) {
+            class.push('-');
+        } else if c.is_alphanumeric() {
+            class.push(c);
+        } else {
+            class.push('\\');
+            class.push(c);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `start`の位置から始まる`{a,b}`をトップレベルの`,`で分割します
+///
+/// 分割した各候補と、`}`の直後の位置を返します。閉じ括弧が無い場合は`None`を返します。
+fn split_braces(chars: &[char], start: usize) -> Option<(Vec<&[char]>, usize)> {
+    let mut depth = 0;
+    let mut alternatives = Vec::new();
+    let mut begin = start + 1;
+    let mut i = start + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            '}' => {
+                alternatives.push(&chars[begin..i]);
+                return Some((alternatives, i + 1));
+            }
+            ',' if depth == 0 => {
+                alternatives.push(&chars[begin..i]);
+                begin = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `{a,b}`を展開したパターンのリストを返します
+pub(crate) fn expand_braces(pattern: &str) -> Vec<String> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => {
+                if let Some((alternatives, end)) = split_braces(&chars, i) {
+                    let prefix: String = chars[..i].iter().collect();
+                    let suffix: String = chars[end..].iter().collect();
+                    return alternatives
+                        .into_iter()
+                        .flat_map(|alternative| {
+                            let alternative: String = alternative.iter().collect();
+                            expand_braces(&format!("{}{}{}", prefix, alternative, suffix))
+                        })
+                        .collect();
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    vec![pattern.to_string()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        GlobSet::new(&[pattern.to_string()]).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn test_wildcards() {
+        let cases = [
+            ("*.py", "main.py", true),
+            ("src/*.rs", "src/main.rs", true),
+            ("src/*.rs", "src/bin/main.rs", false),
+            ("src/*.rs", "lib/src/main.rs", false),
+            ("doc/*.md", "doc/readme.md", true),
+            ("ma?n.rs", "main.rs", true),
+            ("src?main.rs", "src/main.rs", false),
+            ("**/*.min.*", "dist/app.min.js", true),
+            (".gitignore", ".gitignore", true),
+            ("*.config.js", "web/jest.config.js", true),
+            ("doc/(a|b).md", "doc/(a|b).md", true),
+            ("doc/(a|b).md", "doc/a.md", false),
+        ];
+
+        for (pattern, path, expected) in cases {
+            assert_eq!(matches(pattern, path), expected, "{} / {}", pattern, path);
+        }
+    }
+
+    #[test]
+    fn test_double_star() {
+        let cases = [
+            ("**/*.js", "app.js", true),
+            ("**/*.js", "src/app/app.js", true),
+            ("src/**/*.ts", "src/main.ts", true),
+            ("src/**/*.ts", "src/a/b/main.ts", true),
+            ("src/**/*.ts", "lib/src/main.ts", false),
+            ("test/**", "test/unit/a.rs", true),
+            ("test/**", "src/test/a.rs", false),
+            ("src/**/test/**/*.spec.js", "src/test/a.spec.js", true),
+            ("src/**/test/**/*.spec.js", "src/a/test/b/c.spec.js", true),
+            // 先頭・末尾・`/`で区切られた位置以外の`**`は`*`と同じ
+            ("src/**.rs", "src/main.rs", true),
+            ("src/**.rs", "src/bin/main.rs", false),
+        ];
+
+        for (pattern, path, expected) in cases {
+            assert_eq!(matches(pattern, path), expected, "{} / {}", pattern, path);
+        }
+    }
+
+    #[test]
+    fn test_character_classes_and_braces() {
+        let cases = [
+            ("[Mm]akefile", "Makefile", true),
+            ("[Mm]akefile", "build/makefile", true),
+            ("[Mm]akefile", "Rakefile", false),
+            ("file[0-9].rs", "file7.rs", true),
+            ("file[!0-9].rs", "file7.rs", false),
+            ("file[!0-9].rs", "fileA.rs", true),
+            ("file[^0-9].rs", "fileA.rs", true),
+            ("[]]x", "]x", true),
+            ("[a-]x", "-x", true),
+            ("src/**/*.{ts,tsx}", "src/app/view.tsx", true),
+            ("src/**/*.{ts,tsx}", "src/app/view.js", false),
+            ("{src,lib}/{a,b{c,d}}.rs", "lib/bd.rs", true),
+            ("{src,lib}/{a,b{c,d}}.rs", "lib/b.rs", false),
+            ("\\*.rs", "*.rs", true),
+            ("\\*.rs", "a.rs", false),
+            ("unclosed[.rs", "unclosed[.rs", true),
+            ("unclosed{.rs", "unclosed{.rs", true),
+        ];
+
+        for (pattern, path, expected) in cases {
+            assert_eq!(matches(pattern, path), expected, "{} / {}", pattern, path);
+        }
+    }
+
+    #[test]
+    fn test_anchoring_and_directories() {
+        let cases = [
+            // `/`を含まないパターンは任意の階層に一致
+            ("target", "target/debug/main.rs", true),
+            ("target", "crates/core/target/main.rs", true),
+            ("main.rs", "src/main.rs", true),
+            // 先頭の`/`はルートに固定
+            ("/target", "target/debug/main.rs", true),
+            ("/target", "crates/target/main.rs", false),
+            // 途中に`/`を含むパターンもルートに固定
+            ("docs/api", "docs/api/index.md", true),
+            ("docs/api", "site/docs/api/index.md", false),
+            // 末尾の`/`はディレクトリにのみ一致
+            ("build/", "build/out.js", true),
+            ("build/", "build", false),
+            ("build/", "src/build/out.js", true),
+        ];
+
+        for (pattern, path, expected) in cases {
+            assert_eq!(matches(pattern, path), expected, "{} / {}", pattern, path);
+        }
+    }
+
+    #[test]
+    fn test_exact_match() {
+        let set = GlobSet::new_exact(&[
+            "/crates/*".to_string(),
+            "/tools/**".to_string(),
+            "!/crates/legacy".to_string(),
+        ])
+        .unwrap();
+
+        assert!(set.is_match("crates/core"));
+        // ディレクトリ以下のパスには一致しない
+        assert!(!set.is_match("crates/core/examples/demo"));
+        assert!(set.is_match("tools/gen/macros"));
+        assert!(!set.is_match("crates/legacy"));
+        assert!(!set.is_match("crates"));
+    }
+
+    #[test]
+    fn test_negation() {
+        let set = GlobSet::new(&[
+            "generated/**".to_string(),
+            "!generated/keep/**".to_string(),
+            "generated/keep/tmp.rs".to_string(),
+        ])
+        .unwrap();
+
+        assert!(set.is_match("generated/a.rs"));
+        assert!(!set.is_match("generated/keep/b.rs"));
+        // 後のパターンが優先される
+        assert!(set.is_match("generated/keep/tmp.rs"));
+        assert!(!set.is_match("src/main.rs"));
+
+        // 否定パターンのみの場合は何にも一致しない
+        let set = GlobSet::new(&["!*.rs".to_string()]).unwrap();
+        assert!(!set.is_match("main.rs"));
+        assert!(!set.is_match("main.py"));
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(expand_braces("*.{ts,tsx}"), vec!["*.ts", "*.tsx"]);
+        assert_eq!(expand_braces("{a,b{c,d}}/x"), vec!["a/x", "bc/x", "bd/x"]);
+        assert_eq!(expand_braces("plain"), vec!["plain"]);
+        assert_eq!(expand_braces("open{"), vec!["open{"]);
+    }
+}
@@ -0,0 +1,213 @@
+//! ファイルをグループにまとめる単位を扱うモジュール
+//!
+//! このモジュールは、ファイルの分析結果をディレクトリや
+//! ユーザーが定義したコンポーネント、ワークスペースのパッケージの単位で集計するための、
+//! グループの判定機能を提供します。
+
+use super::error::AnalyzerError;
+use super::glob::GlobSet;
+use super::workspace::{package_of, Package};
+use std::str::FromStr;
+
+/// ファイルをまとめる単位を表す列挙型
+///
+/// - `Directory`: 先頭から指定された階層までのディレクトリ（`dir:N`）
+/// - `Component`: `components`で定義したコンポーネント（`component`）
+/// - `Package`: Cargo・npmのワークスペースやGoのモジュールから検出したパッケージ（`package`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Directory(usize),
+    Component,
+    Package,
+}
+
+impl FromStr for GroupBy {
+    type Err = AnalyzerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "component" => return Ok(Self::Component),
+            "package" => return Ok(Self::Package),
+            _ => {}
+        }
+        s.strip_prefix("dir:")
+            .and_then(|depth| depth.parse::<usize>().ok())
+            .filter(|depth| *depth > 0)
+            .map(Self::Directory)
+            .ok_or_else(|| {
+                AnalyzerError::InvalidOption(format!(
+                    "Unknown group: {} (expected dir:N, component or package)",
+                    s
+                ))
+            })
+    }
+}
+
+/// 名前とgitignore形式のパターンで定義した論理的なコンポーネント
+///
+/// # フィールド
+///
+/// - `name`: コンポーネントの名前
+/// - `patterns`: コンポーネントに含まれるファイルのパターン
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Component {
+    pub name: String,
+    pub patterns: Vec<String>,
+}
+
+impl FromStr for Component {
+    type Err = AnalyzerError;
+
+    /// `name=pattern1,pattern2`形式の文字列からコンポーネントを作成します
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, patterns) = s
+            .split_once('=')
+            .map(|(name, patterns)| (name.trim(), patterns))
+            .filter(|(name, _)| !name.is_empty())
+            .ok_or_else(|| {
+                AnalyzerError::InvalidOption(format!(
+                    "Invalid component: {} (expected NAME=PATTERN[,PATTERN...])",
+                    s
+                ))
+            })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            patterns: patterns
+                .split(',')
+                .map(str::trim)
+                .filter(|pattern| !pattern.is_empty())
+                .map(str::to_string)
+                .collect(),
+        })
+    }
+}
+
+/// ファイルパスが属するグループを判定する構造体
+pub(crate) enum Grouper {
+    Directory(usize),
+    Component(Vec<(String, GlobSet)>),
+    Package(Vec<Package>),
+}
+
+impl Grouper {
+    /// グループの単位とコンポーネントの定義、検出したパッケージからGrouperインスタンスを作成します
+    ///
+    /// # エラー
+    ///
+    /// コンポーネント単位でコンポーネントが定義されていない場合、
+    /// またはパターンの変換に失敗した場合にエラーを返します
+    pub(crate) fn new(
+        group_by: GroupBy,
+        components: &[Component],
+        packages: &[Package],
+    ) -> Result<Self, AnalyzerError> {
+        match group_by {
+            GroupBy::Directory(depth) => Ok(Self::Directory(depth)),
+            GroupBy::Package => Ok(Self::Package(packages.to_vec())),
+            GroupBy::Component => {
+                if components.is_empty() {
+                    return Err(AnalyzerError::InvalidOption(
+                        "No components are defined for grouping by component".to_string(),
+                    ));
+                }
+                let components = components
+                    .iter()
+                    .map(|component| {
+                        Ok((component.name.clone(), GlobSet::new(&component.patterns)?))
+                    })
+                    .collect::<Result<Vec<_>, AnalyzerError>>()?;
+                Ok(Self::Component(components))
+            }
+        }
+    }
+
+    /// ファイルパスが属するグループの名前を返します
+    ///
+    /// ディレクトリ単位では、階層が浅いファイルはそのファイルのディレクトリ
+    /// （ルート直下のファイルは`.`）に属します。
+    /// コンポーネント単位では、パターンに一致する全てのコンポーネントに属し、
+    /// どのコンポーネントにも一致しないファイルはグループに属しません。
+    /// パッケージ単位では、ファイルを含む最も深いパッケージに属します。
+    pub(crate) fn groups_of(&self, path: &str) -> Vec<String> {
+        match self {
+            Self::Directory(depth) => {
+                let directories: Vec<&str> = path.split('/').collect();
+                let directories = &directories[..directories.len() - 1];
+                if directories.is_empty() {
+                    return vec![".".to_string()];
+                }
+                vec![directories[..directories.len().min(*depth)].join("/")]
+            }
+            Self::Component(components) => components
+                .iter()
+                .filter(|(_, patterns)| patterns.is_match(path))
+                .map(|(name, _)| name.clone())
+                .collect(),
+            Self::Package(packages) => package_of(packages, path)
+                .map(|package| vec![package.name.clone()])
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_from_str() {
+        assert_eq!("dir:2".parse::<GroupBy>().unwrap(), GroupBy::Directory(2));
+        assert_eq!("component".parse::<GroupBy>().unwrap(), GroupBy::Component);
+        assert_eq!("package".parse::<GroupBy>().unwrap(), GroupBy::Package);
+        assert!("dir:0".parse::<GroupBy>().is_err());
+        assert!("dir".parse::<GroupBy>().is_err());
+        assert!("file".parse::<GroupBy>().is_err());
+    }
+
+    #[test]
+    fn test_component_from_str() {
+        assert_eq!(
+            "api = services/api/**, proto/*.proto"
+                .parse::<Component>()
+                .unwrap(),
+            Component {
+                name: "api".to_string(),
+                patterns: vec!["services/api/**".to_string(), "proto/*.proto".to_string()],
+            }
+        );
+        assert!("services/api/**".parse::<Component>().is_err());
+        assert!("=src/**".parse::<Component>().is_err());
+    }
+
+    #[test]
+    fn test_groups_of() {
+        let grouper = Grouper::new(GroupBy::Directory(2), &[], &[]).unwrap();
+        assert_eq!(
+            grouper.groups_of("src/analyzer/git.rs"),
+            vec!["src/analyzer"]
+        );
+        assert_eq!(
+            grouper.groups_of("src/analyzer/nested/a.rs"),
+            vec!["src/analyzer"]
+        );
+        assert_eq!(grouper.groups_of("src/lib.rs"), vec!["src"]);
+        assert_eq!(grouper.groups_of("main.rs"), vec!["."]);
+
+        let components = vec![
+            "core=src/analyzer/**".parse::<Component>().unwrap(),
+            "git=src/analyzer/git.rs,src/analyzer/diff.rs"
+                .parse()
+                .unwrap(),
+        ];
+        let grouper = Grouper::new(GroupBy::Component, &components, &[]).unwrap();
+        assert_eq!(
+            grouper.groups_of("src/analyzer/git.rs"),
+            vec!["core", "git"]
+        );
+        assert_eq!(grouper.groups_of("src/analyzer/mod.rs"), vec!["core"]);
+        assert!(grouper.groups_of("src/main.rs").is_empty());
+
+        assert!(Grouper::new(GroupBy::Component, &[], &[]).is_err());
+    }
+}
@@ -0,0 +1,334 @@
+//! リポジトリ内のファイルから除外ルールを読み込むモジュール
+//!
+//! このモジュールは、分析対象のリビジョンにコミットされている`.gitignore`、`.gitattributes`、
+//! `.hotspotignore`を読み込み、リポジトリ自身が宣言した分析対象外のファイルを
+//! 判定する機能を提供します。
+//!
+//! - `.gitignore`と`.hotspotignore`: gitignore形式のパターンに一致するファイルを除外します
+//! - `.gitattributes`: `linguist-generated`、`linguist-vendored`が設定されたファイルと、
+//!   `-diff`（`binary`を含む）が指定されたファイルを除外します
+//!
+//! サブディレクトリに置かれたファイルのパターンは、そのディレクトリからの相対パスとして扱います。
+
+use super::error::AnalyzerError;
+use super::glob::GlobSet;
+use super::options::AnalyzerOptions;
+use git2::{Repository, Tree, TreeWalkMode, TreeWalkResult};
+
+const HOTSPOTIGNORE_FILE: &str = ".hotspotignore";
+const GITIGNORE_FILE: &str = ".gitignore";
+const GITATTRIBUTES_FILE: &str = ".gitattributes";
+
+/// `.gitattributes`で分析から除外する属性と、除外する場合の値
+///
+/// `diff`は`-diff`（差分を表示しない）の場合に除外します。
+const EXCLUDED_ATTRIBUTES: &[(&str, bool)] = &[
+    ("linguist-generated", true),
+    ("linguist-vendored", true),
+    ("diff", false),
+];
+
+/// リポジトリ内のファイルから読み込んだ除外ルールを保持する構造体
+///
+/// ルールの種類（無視ファイル、属性）ごとに`GlobSet`を持ち、
+/// いずれかに一致したファイルを除外します。
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RepositoryRules {
+    excludes: Vec<GlobSet>,
+}
+
+impl RepositoryRules {
+    /// 分析対象のリビジョンのツリーから、分析オプションで有効になっているファイルのルールを読み込みます
+    ///
+    /// ツリーが存在しない場合（コミットが1つも無いリポジトリ）や、対象のファイルが無い場合は空のルールを返します。
+    ///
+    /// # エラー
+    ///
+    /// ファイルの読み込みやパターンの変換に失敗した場合にエラーを返します
+    pub(crate) fn load(
+        repo: &Repository,
+        tree: Option<&Tree<'_>>,
+        options: &AnalyzerOptions,
+    ) -> Result<Self, AnalyzerError> {
+        let mut file_names = Vec::new();
+        if options.use_gitignore {
+            file_names.push(GITIGNORE_FILE);
+        }
+        if options.use_hotspotignore {
+            file_names.push(HOTSPOTIGNORE_FILE);
+        }
+        if options.use_gitattributes {
+            file_names.push(GITATTRIBUTES_FILE);
+        }
+        if file_names.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let Some(tree) = tree else {
+            return Ok(Self::default());
+        };
+
+        // 上位のディレクトリのファイルを先に読み、下位のディレクトリのルールを優先させる
+        let mut files = Vec::new();
+        tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+            if let Some(name) = entry.name().filter(|name| file_names.contains(name)) {
+                files.push((
+                    dir.trim_end_matches('/').to_string(),
+                    name.to_string(),
+                    entry.id(),
+                ));
+            }
+            TreeWalkResult::Ok
+        })?;
+        files.sort_by_key(|(dir, _, _)| dir.matches('/').count() + usize::from(!dir.is_empty()));
+
+        let mut ignore_patterns = Vec::new();
+        let mut attribute_patterns = vec![Vec::new(); EXCLUDED_ATTRIBUTES.len()];
+        for (dir, name, id) in files {
+            let blob = repo.find_blob(id)?;
+            let content = String::from_utf8_lossy(blob.content());
+            if name == GITATTRIBUTES_FILE {
+                for (pattern, attributes) in parse_gitattributes(&content) {
+                    for (index, excluded) in attribute_exclusions(&attributes) {
+                        attribute_patterns[index].push(scoped_pattern(&dir, &pattern, !excluded));
+                    }
+                }
+            } else {
+                ignore_patterns.extend(
+                    parse_ignore_file(&content).map(|pattern| scoped_pattern(&dir, pattern, false)),
+                );
+            }
+        }
+
+        let excludes = std::iter::once(ignore_patterns)
+            .chain(attribute_patterns)
+            .filter(|patterns| !patterns.is_empty())
+            .map(|patterns| GlobSet::new(&patterns))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { excludes })
+    }
+
+    /// ファイルパスがリポジトリのルールによって除外されるかどうかを判定します
+    pub(crate) fn is_excluded(&self, path: &str) -> bool {
+        self.excludes.iter().any(|set| set.is_match(path))
+    }
+
+    /// 読み込んだルールのパターンを返します（キャッシュの識別に使用します）
+    pub(crate) fn sources(&self) -> Vec<Vec<&str>> {
+        self.excludes.iter().map(|set| set.sources()).collect()
+    }
+}
+
+/// gitignore形式のファイルから、空行とコメントを除いたパターンを取り出します
+fn parse_ignore_file(content: &str) -> impl Iterator<Item = &str> {
+    content
+        .lines()
+        .map(trim_pattern)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// `.gitattributes`の各行をパターンと属性のリストに分割します
+///
+/// 空行・コメント行と、`.gitattributes`では使用できない否定パターンの行は無視します。
+fn parse_gitattributes(content: &str) -> Vec<(String, Vec<&str>)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pattern = fields.next()?;
+            if pattern.starts_with('#') || pattern.starts_with('!') {
+                return None;
+            }
+            Some((pattern.to_string(), fields.collect()))
+        })
+        .collect()
+}
+
+/// 属性のリストから、`EXCLUDED_ATTRIBUTES`の各属性について除外するかどうかを求めます
+///
+/// 属性が指定されていない場合は結果に含めません。`!attr`で未指定に戻した場合は
+/// 除外しないものとして扱います。
+///
+/// # 戻り値
+///
+/// `EXCLUDED_ATTRIBUTES`のインデックスと、除外するかどうかの組のリスト
+fn attribute_exclusions(attributes: &[&str]) -> Vec<(usize, bool)> {
+    let mut exclusions = Vec::new();
+    for attribute in attributes {
+        // `binary`は`-diff -merge -text`のマクロ
+        let attribute = if *attribute == "binary" {
+            "-diff"
+        } else {
+            attribute
+        };
+        let (name, value) = if let Some(name) = attribute.strip_prefix('-') {
+            (name, Some(false))
+        } else if let Some(name) = attribute.strip_prefix('!') {
+            (name, None)
+        } else if let Some((name, value)) = attribute.split_once('=') {
+            (name, Some(value != "false"))
+        } else {
+            (attribute, Some(true))
+        };
+
+        if let Some(index) = EXCLUDED_ATTRIBUTES
+            .iter()
+            .position(|(excluded_name, _)| *excluded_name == name)
+        {
+            let excluded = value == Some(EXCLUDED_ATTRIBUTES[index].1);
+            exclusions.retain(|(i, _)| *i != index);
+            exclusions.push((index, excluded));
+        }
+    }
+    exclusions
+}
+
+/// 行末の空白を取り除きます（`\`でエスケープされた空白は残します）
+fn trim_pattern(line: &str) -> &str {
+    let trimmed = line.trim_end();
+    if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    }
+}
+
+/// `dir`に置かれたファイルのパターンを、リポジトリのルートからのパターンに変換します
+///
+/// `negate`が`true`の場合は否定パターンにします。
+fn scoped_pattern(dir: &str, pattern: &str, negate: bool) -> String {
+    let (negated, pattern) = match pattern.strip_prefix('!') {
+        Some(pattern) => (!negate, pattern),
+        None => (negate, pattern),
+    };
+    let prefix = if negated { "!" } else { "" };
+
+    if dir.is_empty() {
+        return format!("{}{}", prefix, pattern);
+    }
+    if pattern.trim_end_matches('/').contains('/') {
+        // 先頭または途中に`/`を含むパターンはファイルが置かれたディレクトリを基準とする
+        format!(
+            "{}{}/{}",
+            prefix,
+            dir,
+            pattern.strip_prefix('/').unwrap_or(pattern)
+        )
+    } else {
+        format!("{}{}/**/{}", prefix, dir, pattern)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::test_utils::commit_files;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_scoped_pattern() {
+        assert_eq!(scoped_pattern("", "*.pb.go", false), "*.pb.go");
+        assert_eq!(scoped_pattern("", "!keep.rs", false), "!keep.rs");
+        assert_eq!(scoped_pattern("web", "dist/", false), "web/**/dist/");
+        assert_eq!(scoped_pattern("web", "/dist", false), "web/dist");
+        assert_eq!(
+            scoped_pattern("web", "src/gen/*.ts", true),
+            "!web/src/gen/*.ts"
+        );
+        assert_eq!(scoped_pattern("web", "!keep.ts", false), "!web/**/keep.ts");
+    }
+
+    #[test]
+    fn test_attribute_exclusions() {
+        assert_eq!(
+            attribute_exclusions(&["linguist-generated"]),
+            vec![(0, true)]
+        );
+        assert_eq!(
+            attribute_exclusions(&["linguist-generated=true", "linguist-vendored=false"]),
+            vec![(0, true), (1, false)]
+        );
+        assert_eq!(attribute_exclusions(&["-diff"]), vec![(2, true)]);
+        assert_eq!(attribute_exclusions(&["binary"]), vec![(2, true)]);
+        assert_eq!(attribute_exclusions(&["diff=go"]), vec![(2, false)]);
+        assert_eq!(
+            attribute_exclusions(&["!linguist-generated"]),
+            vec![(0, false)]
+        );
+        assert_eq!(attribute_exclusions(&["text", "eol=lf"]), vec![]);
+    }
+
+    #[test]
+    fn test_parse_files() {
+        let ignore = "# comment\n\n*.log   \ntrailing\\ \n\\#literal\n";
+        assert_eq!(
+            parse_ignore_file(ignore).collect::<Vec<_>>(),
+            vec!["*.log", "trailing\\ ", "\\#literal"]
+        );
+
+        let attributes = "# comment\n*.pb.go linguist-generated=true\n!neg -diff\n\n*.png binary\n";
+        assert_eq!(
+            parse_gitattributes(attributes),
+            vec![
+                ("*.pb.go".to_string(), vec!["linguist-generated=true"]),
+                ("*.png".to_string(), vec!["binary"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_rules_from_tree() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let repo = Repository::init(temp_dir.path())?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                (".gitignore", Some("*.tmp\n")),
+                (".hotspotignore", Some("docs/\n!docs/api.rs\n")),
+                (
+                    ".gitattributes",
+                    Some("*.pb.go linguist-generated\nthird_party/** linguist-vendored\n*.dat -diff\n"),
+                ),
+                ("web/.gitattributes", Some("gen/** linguist-generated\ngen/keep.ts -linguist-generated\n")),
+                ("main.go", Some("package main\n")),
+            ],
+            "Add rules",
+        )?;
+
+        let tree = repo.head()?.peel_to_tree()?;
+        let options = AnalyzerOptions {
+            use_gitignore: true,
+            ..Default::default()
+        };
+        let rules = RepositoryRules::load(&repo, Some(&tree), &options)?;
+        assert!(rules.is_excluded("build/out.tmp"));
+        assert!(rules.is_excluded("docs/guide.rs"));
+        assert!(!rules.is_excluded("docs/api.rs"));
+        assert!(rules.is_excluded("api/v1/service.pb.go"));
+        assert!(rules.is_excluded("third_party/lib/a.c"));
+        assert!(rules.is_excluded("assets/data.dat"));
+        assert!(rules.is_excluded("web/gen/client.ts"));
+        assert!(!rules.is_excluded("web/gen/keep.ts"));
+        assert!(!rules.is_excluded("gen/client.ts"));
+        assert!(!rules.is_excluded("main.go"));
+
+        // 無効にしたファイルのルールは読み込まない
+        let options = AnalyzerOptions {
+            use_gitattributes: false,
+            use_hotspotignore: false,
+            ..Default::default()
+        };
+        let rules = RepositoryRules::load(&repo, Some(&tree), &options)?;
+        assert!(!rules.is_excluded("build/out.tmp"));
+        assert!(!rules.is_excluded("api/v1/service.pb.go"));
+        assert!(rules.sources().is_empty());
+
+        // ツリーが無い場合は空のルールを返す
+        let rules = RepositoryRules::load(&repo, None, &AnalyzerOptions::default())?;
+        assert!(!rules.is_excluded("docs/guide.rs"));
+
+        Ok(())
+    }
+}
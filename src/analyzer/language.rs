@@ -0,0 +1,255 @@
+//! プログラミング言語の判定を担当するモジュール
+//!
+//! このモジュールは、ファイルの拡張子による言語の判定と、
+//! 言語をまとめたプリセットからのインクルードパターンの生成、
+//! `HEAD`のツリーに含まれるファイルからの言語の自動検出機能を提供します。
+
+use super::error::AnalyzerError;
+use git2::{ObjectType, Repository, TreeWalkMode, TreeWalkResult};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 言語の名前と、その言語のファイルの拡張子（小文字）
+pub const LANGUAGES: &[(&str, &[&str])] = &[
+    ("c", &["c", "h"]),
+    ("clojure", &["clj", "cljs", "cljc"]),
+    ("cpp", &["cc", "cpp", "cxx", "hh", "hpp", "hxx"]),
+    ("csharp", &["cs"]),
+    ("css", &["css", "scss", "sass", "less"]),
+    ("dart", &["dart"]),
+    ("elixir", &["ex", "exs"]),
+    ("erlang", &["erl", "hrl"]),
+    ("fsharp", &["fs", "fsi", "fsx"]),
+    ("go", &["go"]),
+    ("groovy", &["groovy", "gradle"]),
+    ("haskell", &["hs"]),
+    ("html", &["html", "htm"]),
+    ("java", &["java"]),
+    ("javascript", &["js", "jsx", "mjs", "cjs"]),
+    ("kotlin", &["kt", "kts"]),
+    ("lua", &["lua"]),
+    ("objective-c", &["m", "mm"]),
+    ("perl", &["pl", "pm"]),
+    ("php", &["php"]),
+    ("python", &["py", "pyi"]),
+    ("ruby", &["rb", "rake"]),
+    ("rust", &["rs"]),
+    ("scala", &["scala", "sc"]),
+    ("shell", &["sh", "bash", "zsh"]),
+    ("sql", &["sql"]),
+    ("svelte", &["svelte"]),
+    ("swift", &["swift"]),
+    ("typescript", &["ts", "tsx", "mts", "cts"]),
+    ("vue", &["vue"]),
+];
+
+/// 名前付きのプリセットと、プリセットに含まれる言語
+///
+/// `--preset`には、プリセットの名前の他に`LANGUAGES`の言語名も指定できます。
+pub const LANGUAGE_PRESETS: &[(&str, &[&str])] = &[
+    (
+        "default",
+        &[
+            "c",
+            "cpp",
+            "go",
+            "java",
+            "javascript",
+            "python",
+            "rust",
+            "typescript",
+        ],
+    ),
+    ("jvm", &["clojure", "groovy", "java", "kotlin", "scala"]),
+    (
+        "web",
+        &["css", "html", "javascript", "svelte", "typescript", "vue"],
+    ),
+    (
+        "mobile",
+        &["dart", "java", "kotlin", "objective-c", "swift"],
+    ),
+    ("dotnet", &["csharp", "fsharp"]),
+    ("systems", &["c", "cpp", "go", "rust"]),
+    (
+        "scripting",
+        &["lua", "perl", "php", "python", "ruby", "shell"],
+    ),
+];
+
+/// 自動検出で言語を選択するために必要な、判定できたファイル全体に占める割合
+///
+/// 設定やビルド用の少数のスクリプトなどで言語が選択されないようにします。
+const AUTO_DETECT_MIN_SHARE: f64 = 0.05;
+
+/// ファイルパスの拡張子から言語を判定します
+///
+/// 判定できない場合は`None`を返します。
+pub fn language_of(path: &str) -> Option<&'static str> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    let (stem, extension) = file_name.rsplit_once('.')?;
+    if stem.is_empty() {
+        return None;
+    }
+    let extension = extension.to_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|(_, extensions)| extensions.contains(&extension.as_str()))
+        .map(|(name, _)| *name)
+}
+
+/// プリセットまたは言語名から、その言語のファイルに一致するインクルードパターンを生成します
+///
+/// `all`を指定すると`LANGUAGES`の全ての言語を対象とします。
+///
+/// # エラー
+///
+/// プリセットと言語のどちらにも該当しない名前の場合にエラーを返します
+pub fn preset_patterns(name: &str) -> Result<Vec<String>, AnalyzerError> {
+    let languages: Vec<&str> = if name == "all" {
+        LANGUAGES.iter().map(|(language, _)| *language).collect()
+    } else if let Some((_, languages)) = LANGUAGE_PRESETS.iter().find(|(preset, _)| *preset == name)
+    {
+        languages.to_vec()
+    } else if LANGUAGES.iter().any(|(language, _)| *language == name) {
+        vec![name]
+    } else {
+        return Err(AnalyzerError::InvalidOption(format!(
+            "Unknown preset or language: {}",
+            name
+        )));
+    };
+
+    Ok(language_patterns(&languages))
+}
+
+/// 言語のファイルに一致するインクルードパターンを生成します
+///
+/// 未知の言語名は無視します。
+pub fn language_patterns(languages: &[&str]) -> Vec<String> {
+    LANGUAGES
+        .iter()
+        .filter(|(language, _)| languages.contains(language))
+        .map(|(_, extensions)| match extensions {
+            [extension] => format!("**/*.{}", extension),
+            _ => format!("**/*.{{{}}}", extensions.join(",")),
+        })
+        .collect()
+}
+
+/// 自動検出された言語を表す構造体
+///
+/// # フィールド
+///
+/// - `language`: 言語の名前
+/// - `files`: `HEAD`のツリーに含まれるその言語のファイルの数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedLanguage {
+    pub language: &'static str,
+    pub files: usize,
+}
+
+/// `HEAD`のツリーに含まれるファイルの拡張子から、リポジトリで使用されている言語を検出します
+///
+/// 判定できたファイル全体の5%以上を占める言語を、ファイル数の多い順に返します。
+///
+/// # エラー
+///
+/// リポジトリのオープンや`HEAD`のツリーの読み込みに失敗した場合にエラーを返します
+pub fn detect_languages(path: impl AsRef<Path>) -> Result<Vec<DetectedLanguage>, AnalyzerError> {
+    let repo = Repository::open(path)?;
+    let tree = repo.head()?.peel_to_tree()?;
+
+    let mut counts: HashMap<&'static str, usize> = HashMap::new();
+    tree.walk(TreeWalkMode::PreOrder, |_, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(language) = entry.name().and_then(language_of) {
+                *counts.entry(language).or_insert(0) += 1;
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+
+    let total: usize = counts.values().sum();
+    let mut detected: Vec<DetectedLanguage> = counts
+        .into_iter()
+        .filter(|(_, files)| *files as f64 >= total as f64 * AUTO_DETECT_MIN_SHARE)
+        .map(|(language, files)| DetectedLanguage { language, files })
+        .collect();
+    detected.sort_by(|a, b| b.files.cmp(&a.files).then(a.language.cmp(b.language)));
+    Ok(detected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::test_utils::commit_files;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_language_of() {
+        assert_eq!(language_of("src/main.rs"), Some("rust"));
+        assert_eq!(language_of("app/src/Main.KT"), Some("kotlin"));
+        assert_eq!(language_of("web/App.tsx"), Some("typescript"));
+        assert_eq!(language_of("include/util.h"), Some("c"));
+        assert_eq!(language_of("README.md"), None);
+        assert_eq!(language_of("Makefile"), None);
+        assert_eq!(language_of("config/.rs"), None);
+    }
+
+    #[test]
+    fn test_preset_patterns() {
+        assert_eq!(
+            preset_patterns("jvm").unwrap(),
+            vec![
+                "**/*.{clj,cljs,cljc}",
+                "**/*.{groovy,gradle}",
+                "**/*.java",
+                "**/*.{kt,kts}",
+                "**/*.{scala,sc}",
+            ]
+        );
+        assert_eq!(preset_patterns("ruby").unwrap(), vec!["**/*.{rb,rake}"]);
+        assert_eq!(preset_patterns("all").unwrap().len(), LANGUAGES.len());
+        assert!(preset_patterns("cobol").is_err());
+
+        // プリセットには既知の言語だけが含まれる
+        for (_, languages) in LANGUAGE_PRESETS {
+            assert_eq!(language_patterns(languages).len(), languages.len());
+        }
+    }
+
+    #[test]
+    fn test_detect_languages() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let repo = Repository::init(temp_dir.path())?;
+        let mut files: Vec<(String, String)> = (0..30)
+            .map(|i| (format!("app/module{}.py", i), "pass\n".to_string()))
+            .collect();
+        files.extend((0..10).map(|i| (format!("cmd/tool{}.go", i), "package main\n".to_string())));
+        files.push(("scripts/setup.sh".to_string(), "echo\n".to_string()));
+        files.push(("README.md".to_string(), "# readme\n".to_string()));
+        let files: Vec<(&str, Option<&str>)> = files
+            .iter()
+            .map(|(path, content)| (path.as_str(), Some(content.as_str())))
+            .collect();
+        commit_files(&repo, "alice", &files, "Add files")?;
+
+        // 全体の5%未満のシェルスクリプトは選択しない
+        assert_eq!(
+            detect_languages(temp_dir.path())?,
+            vec![
+                DetectedLanguage {
+                    language: "python",
+                    files: 30
+                },
+                DetectedLanguage {
+                    language: "go",
+                    files: 10
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -3,6 +3,9 @@
 //! このモジュールは、ホットスポット分析の結果を表現するためのデータ構造と、
 //! 分析結果のシリアライズに関する機能を提供します。
 
+use super::git::SkippedCommit;
+use super::workspace::Package;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// ファイルごとの分析メトリクスを保持する構造体
@@ -15,6 +18,15 @@ use serde::{Deserialize, Serialize};
 /// - `author_count`: ファイルに貢献した開発者の数
 /// - `main_contributor_percentage`: 最も貢献度の高い開発者の貢献割合（%）
 /// - `knowledge_distribution`: 知識分布スコア（0-1）
+/// - `lines_added`: 追加された行数の合計
+/// - `lines_deleted`: 削除された行数の合計
+/// - `churn`: 追加・削除された行数の合計
+/// - `sum_of_coupling`: 各コミットで一緒に変更された他のファイルの数の合計。分析対象のファイルを`sum_of_coupling_max_changeset_size`より多く変更したコミットと、削除されたファイル（`include_deleted`が`false`の場合）は数えない。計算しない場合は`None`
+/// - `lines_of_code`: 分析対象のリビジョンにおける、空行を除いた行数。計算しない場合やファイルが存在しない場合は`None`
+/// - `complexity`: 分析対象のリビジョンにおける、インデントに基づく複雑さ（各行のインデントの段数の合計）
+/// - `cyclomatic_complexity`: 分析対象のリビジョンにおける循環的複雑度。`tree-sitter`フィーチャーが有効で、対応する言語の場合のみ
+/// - `package`: ファイルを含むワークスペースのパッケージの名前。検出しない場合やパッケージ外のファイルは`None`
+/// - `deleted_at`: 分析期間の終了時点で削除されている場合、削除されたコミットの日時
 #[derive(Debug, Serialize, Deserialize)]
 pub struct FileMetrics {
     pub path: String,
@@ -26,6 +38,139 @@ pub struct FileMetrics {
     pub main_contributor_percentage: f64,
     #[serde(serialize_with = "round_to_3", deserialize_with = "deserialize_f64")]
     pub knowledge_distribution: f64,
+    pub lines_added: u64,
+    pub lines_deleted: u64,
+    pub churn: u64,
+    #[serde(default)]
+    pub sum_of_coupling: Option<u64>,
+    #[serde(default)]
+    pub lines_of_code: Option<u32>,
+    #[serde(default, serialize_with = "round_option_to_3")]
+    pub complexity: Option<f64>,
+    #[serde(default)]
+    pub cyclomatic_complexity: Option<u32>,
+    #[serde(default)]
+    pub package: Option<String>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+/// 分析結果全体を保持する構造体
+///
+/// # フィールド
+///
+/// - `files`: ファイルごとの分析メトリクス
+/// - `skipped_commits`: コミットのフィルタによって分析から除外されたコミット
+/// - `diagnostics`: 分析の過程で除外したファイルの集計
+/// - `languages`: 言語ごとの集計（ファイル数の多い順）
+/// - `groups`: ディレクトリまたはコンポーネント、パッケージごとの集計。`group_by`を指定しない場合は空
+/// - `packages`: 検出したワークスペースのパッケージ。検出しない場合は空
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AnalysisReport {
+    pub files: Vec<FileMetrics>,
+    pub skipped_commits: Vec<SkippedCommit>,
+    pub diagnostics: Diagnostics,
+    pub languages: Vec<LanguageMetrics>,
+    #[serde(default)]
+    pub groups: Vec<GroupMetrics>,
+    #[serde(default)]
+    pub packages: Vec<Package>,
+}
+
+/// ディレクトリまたはコンポーネント、パッケージごとの分析結果を保持する構造体
+///
+/// 変更回数や開発者は、グループ内のいずれかのファイルを変更したコミットから求めるため、
+/// ファイルごとの値の合計とは一致しません。
+///
+/// # フィールド
+///
+/// - `group`: ディレクトリのパス、またはコンポーネントやパッケージの名前
+/// - `hotspot_score`: ファイルと同じ計算方法によるホットスポットスコア
+/// - `files`: 変更されたファイルの数
+/// - `revisions`: グループ内のファイルを変更したコミットの数
+/// - `author_count`: グループ内のファイルを変更した開発者の数
+/// - `main_contributor_percentage`: 主要な開発者の貢献度（%）
+/// - `knowledge_distribution`: 知識の分散度（0.0-1.0）
+/// - `lines_added`: 追加された行数の合計
+/// - `lines_deleted`: 削除された行数の合計
+/// - `churn`: 追加・削除された行数の合計
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupMetrics {
+    pub group: String,
+    #[serde(serialize_with = "round_to_3", deserialize_with = "deserialize_f64")]
+    pub hotspot_score: f64,
+    pub files: u32,
+    pub revisions: u32,
+    pub author_count: u32,
+    #[serde(serialize_with = "round_to_3", deserialize_with = "deserialize_f64")]
+    pub main_contributor_percentage: f64,
+    #[serde(serialize_with = "round_to_3", deserialize_with = "deserialize_f64")]
+    pub knowledge_distribution: f64,
+    pub lines_added: u64,
+    pub lines_deleted: u64,
+    pub churn: u64,
+}
+
+/// 一緒に変更される傾向のあるファイルの組（変更の結合度）を表す構造体
+///
+/// # フィールド
+///
+/// - `path`: ファイルのパス
+/// - `coupled_path`: `path`と一緒に変更されたファイルのパス
+/// - `shared_commits`: 2つのファイルを一緒に変更したコミットの数
+/// - `degree`: 結合度（`shared_commits`を2つのファイルの変更回数の平均で割った値、0.0-1.0）
+/// - `revisions`: `path`の変更回数
+/// - `coupled_revisions`: `coupled_path`の変更回数
+/// - `repository`: 複数のリポジトリを分析した場合、`path`を含むリポジトリの名前。それ以外は`None`
+/// - `coupled_repository`: 複数のリポジトリを分析した場合、`coupled_path`を含むリポジトリの名前。それ以外は`None`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileCoupling {
+    pub path: String,
+    pub coupled_path: String,
+    pub shared_commits: u32,
+    #[serde(serialize_with = "round_to_3", deserialize_with = "deserialize_f64")]
+    pub degree: f64,
+    pub revisions: u32,
+    pub coupled_revisions: u32,
+    #[serde(default)]
+    pub repository: Option<String>,
+    #[serde(default)]
+    pub coupled_repository: Option<String>,
+}
+
+/// 言語ごとの集計を保持する構造体
+///
+/// 拡張子から言語を判定できたファイルを対象に集計します。
+///
+/// # フィールド
+///
+/// - `language`: 言語の名前
+/// - `files`: 変更されたファイルの数
+/// - `revisions`: ファイルの変更回数の合計
+/// - `author_count`: いずれかのファイルを変更した開発者の数
+/// - `lines_added`: 追加された行数の合計
+/// - `lines_deleted`: 削除された行数の合計
+/// - `churn`: 追加・削除された行数の合計
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageMetrics {
+    pub language: String,
+    pub files: u32,
+    pub revisions: u32,
+    pub author_count: u32,
+    pub lines_added: u64,
+    pub lines_deleted: u64,
+    pub churn: u64,
+}
+
+/// 分析対象のパターンに一致したが、内容によって除外したファイルの集計
+///
+/// # フィールド
+///
+/// - `binary_files`: バイナリのため除外したファイルの数
+/// - `generated_files`: 自動生成されたファイルとして除外したファイルの数
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostics {
+    pub binary_files: usize,
+    pub generated_files: usize,
 }
 
 /// 浮動小数点数を3桁に丸める補助関数
@@ -41,6 +186,17 @@ where
     serializer.serialize_f64((*value * 1000.0).round() / 1000.0)
 }
 
+/// `Option`の浮動小数点数を3桁に丸める補助関数
+fn round_option_to_3<S>(value: &Option<f64>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match value {
+        Some(value) => serializer.serialize_some(&((*value * 1000.0).round() / 1000.0)),
+        None => serializer.serialize_none(),
+    }
+}
+
 /// f64値をデシリアライズする補助関数
 fn deserialize_f64<'de, D>(deserializer: D) -> Result<f64, D::Error>
 where
@@ -62,6 +218,15 @@ mod tests {
             author_count: 5,
             main_contributor_percentage: 45.6789,
             knowledge_distribution: 0.54321,
+            lines_added: 120,
+            lines_deleted: 30,
+            churn: 150,
+            sum_of_coupling: Some(12),
+            lines_of_code: Some(80),
+            complexity: Some(31.25),
+            cyclomatic_complexity: None,
+            package: Some("app-core".to_string()),
+            deleted_at: None,
         };
 
         let json = serde_json::to_string(&metrics).unwrap();
@@ -70,6 +235,12 @@ mod tests {
         assert_eq!(metrics.path, deserialized.path);
         assert_eq!(metrics.revisions, deserialized.revisions);
         assert_eq!(metrics.author_count, deserialized.author_count);
+        assert_eq!(metrics.churn, deserialized.churn);
+        assert_eq!(metrics.sum_of_coupling, deserialized.sum_of_coupling);
+        assert_eq!(metrics.package, deserialized.package);
+        assert_eq!(metrics.lines_of_code, deserialized.lines_of_code);
+        assert_eq!(deserialized.complexity, Some(31.25));
+        assert_eq!(deserialized.cyclomatic_complexity, None);
 
         // 丸められた値の検証
         assert!((deserialized.hotspot_score - 12.346).abs() < 0.001);
@@ -13,16 +13,53 @@
 //! - `HotspotAnalyzer`: 分析プロセス全体を制御する主要なクラス
 //! - `FileMetrics`: 個々のファイルの分析結果を保持する構造体
 //! - `FileStats`: ファイルごとの統計情報を収集する内部構造体
-
+//! - `AnalyzerOptions`: 分析の動作を制御する設定値
+
+mod author;
+mod cache;
+mod complexity;
+mod config;
+mod coupling;
+mod date;
+mod diff;
 mod error;
 mod git;
+mod glob;
+mod group;
+mod ignore;
+mod language;
 mod metrics;
-
+mod options;
+#[cfg(test)]
+mod test_utils;
+mod workspace;
+
+pub use author::{AuthorAliases, AuthorKey, CoauthorCredit, DEFAULT_BOT_AUTHORS};
+pub use complexity::CodeComplexity;
+pub use config::{ProjectConfig, ScoreConfig, CONFIG_FILE_NAME};
+pub use coupling::{ChangeSetKind, DEFAULT_TICKET_PATTERN};
+pub use date::parse_date;
+pub use diff::{MergeMode, GENERATED_FILE_MARKERS};
 pub use error::AnalyzerError;
 use git::GitRepository;
-pub use metrics::FileMetrics;
-
-use chrono::Utc;
+pub use git::{SkipReason, SkippedCommit};
+use group::Grouper;
+pub use group::{Component, GroupBy};
+pub use language::{
+    detect_languages, language_of, language_patterns, preset_patterns, DetectedLanguage, LANGUAGES,
+    LANGUAGE_PRESETS,
+};
+pub use metrics::{
+    AnalysisReport, Diagnostics, FileCoupling, FileMetrics, GroupMetrics, LanguageMetrics,
+};
+pub use options::{
+    AnalyzerOptions, CouplingOptions, ScoreWeights, DEFAULT_COAUTHOR_TRAILERS,
+    DEFAULT_RENAME_THRESHOLD,
+};
+use workspace::package_of;
+pub use workspace::{Package, PackageKind};
+
+use chrono::{DateTime, Utc};
 use std::collections::{HashMap, HashSet};
 
 /// ホットスポット分析を実行するメインの構造体
@@ -33,10 +70,14 @@ use std::collections::{HashMap, HashSet};
 /// # フィールド
 ///
 /// - `repo`: Gitリポジトリへのアクセスを管理するインスタンス
-/// - `time_window_days`: 分析対象期間（日数）
+/// - `options`: 分析オプション
+/// - `grouper`: ファイルが属するグループを判定するインスタンス。グループ単位で集計しない場合は`None`
+/// - `packages`: 検出したワークスペースのパッケージ。検出しない場合は空
 pub struct HotspotAnalyzer {
     repo: GitRepository,
-    time_window_days: i64,
+    options: AnalyzerOptions,
+    grouper: Option<Grouper>,
+    packages: Vec<Package>,
 }
 
 impl HotspotAnalyzer {
@@ -48,7 +89,7 @@ impl HotspotAnalyzer {
     /// - `time_window_days`: 分析対象期間（日数）
     /// - `include_patterns`: 分析対象とするファイルパターンのリスト
     /// - `exclude_patterns`: 分析から除外するファイルパターンのリスト
-    /// - `include_merges`: マージコミットを含めるかどうか
+    /// - `include_merges`: マージコミットを含めるかどうか（含める場合は第1親との差分を使用）
     ///
     /// # エラー
     ///
@@ -62,9 +103,53 @@ impl HotspotAnalyzer {
         exclude_patterns: Vec<String>,
         include_merges: bool,
     ) -> Result<Self, AnalyzerError> {
+        Self::with_options(
+            path,
+            AnalyzerOptions {
+                time_window_days,
+                include_patterns,
+                exclude_patterns,
+                merge_mode: if include_merges {
+                    MergeMode::FirstParent
+                } else {
+                    MergeMode::Skip
+                },
+                ..Default::default()
+            },
+        )
+    }
+
+    /// 分析オプションを指定してHotspotAnalyzerインスタンスを作成します
+    ///
+    /// # 引数
+    ///
+    /// - `path`: 分析対象のGitリポジトリパス
+    /// - `options`: 分析オプション
+    ///
+    /// # エラー
+    ///
+    /// `new`と同様の場合の他、コンポーネント単位の集計でコンポーネントが
+    /// 定義されていない場合や、パッケージの検出に失敗した場合にエラーを返します
+    pub fn with_options(
+        path: impl AsRef<std::path::Path>,
+        options: AnalyzerOptions,
+    ) -> Result<Self, AnalyzerError> {
+        let repo = GitRepository::open(path, &options)?;
+        let packages = if options.detect_packages || options.group_by == Some(GroupBy::Package) {
+            repo.packages()?
+        } else {
+            Vec::new()
+        };
+        let grouper = options
+            .group_by
+            .map(|group_by| Grouper::new(group_by, &options.components, &packages))
+            .transpose()?;
+
         Ok(Self {
-            repo: GitRepository::open(path, include_patterns, exclude_patterns, include_merges)?,
-            time_window_days,
+            repo,
+            options,
+            grouper,
+            packages,
         })
     }
 
@@ -80,67 +165,504 @@ impl HotspotAnalyzer {
     /// - Gitリポジトリの操作に失敗
     /// - コミット履歴の取得に失敗
     pub fn analyze(&self) -> Result<Vec<FileMetrics>, AnalyzerError> {
-        let since = Utc::now() - chrono::Duration::days(self.time_window_days);
-        let commits = self.repo.get_commits_since(since)?;
+        Ok(self.analyze_report()?.files)
+    }
 
-        let mut file_stats: HashMap<String, FileStats> = HashMap::new();
+    /// リポジトリの分析を実行し、ホットスポットメトリクスと分析から除外したコミットを返します
+    ///
+    /// `group_by`が指定されている場合は、グループごとのメトリクスも計算します。
+    /// グループの変更回数や開発者は、グループ内のファイルを変更したコミットから求めます
+    /// （1つのコミットがグループ内の複数のファイルを変更しても1回と数えます）。
+    ///
+    /// # エラー
+    ///
+    /// `analyze`と同様の場合にエラーを返します
+    pub fn analyze_report(&self) -> Result<AnalysisReport, AnalyzerError> {
+        let (since, until) = self.analysis_period()?;
+        let history = self.repo.get_commits_since(since, until)?;
+        let commits = history.commits;
 
+        let mut file_stats: HashMap<String, FileStats> = HashMap::new();
+        let mut group_stats: HashMap<String, GroupStats> = HashMap::new();
+        let mut resolver = PathResolver::new(self.options.follow_renames);
+        let sum_of_coupling =
+            self.options.sum_of_coupling || self.options.score_weights.sum_of_coupling > 0.0;
+        // sum_of_couplingの計算に使用する、コミットごとに変更されたファイル
+        let mut change_sets: Vec<Vec<String>> = Vec::new();
+        // グループの集計に使用する、コミットごとの貢献度と変更されたファイル
+        let mut group_changes = Vec::new();
+
+        // コミットは新しい順に並んでいるため、リネームを記録した後に現れる
+        // 古いパスへの変更は現在のパスへ集約できる。
+        // 同様に、ファイルごとに最初に現れる変更が削除であれば、そのファイルは
+        // 分析期間の終了時点で存在しない
         for commit in commits {
-            let author = commit.author.clone();
-            for file_path in commit.files {
-                let stats = file_stats.entry(file_path).or_default();
+            let credits = self.author_credits(&commit);
+            let deleted_stats = || FileStats {
+                deleted_at: Some(commit.timestamp),
+                ..Default::default()
+            };
+            let mut changed_files = Vec::new();
+            let mut group_files = Vec::new();
+
+            for change in commit.files {
+                let (file_path, removed_path) = resolver.resolve(change.path, change.old_path);
+                if sum_of_coupling {
+                    changed_files.push(file_path.clone());
+                }
+                if let Some(removed_path) = removed_path {
+                    file_stats.entry(removed_path).or_insert_with(deleted_stats);
+                }
+
+                if self.grouper.is_some() {
+                    group_files.push((file_path.clone(), change.lines_added, change.lines_deleted));
+                }
+
+                let stats = if change.deleted {
+                    file_stats.entry(file_path).or_insert_with(deleted_stats)
+                } else {
+                    file_stats.entry(file_path).or_default()
+                };
 
                 stats.revisions += 1;
-                stats.authors.insert(author.clone());
-                *stats.author_commits.entry(author.clone()).or_insert(0) += 1;
+                stats.lines_added += change.lines_added as u64;
+                stats.lines_deleted += change.lines_deleted as u64;
+                stats.add_credits(&credits);
+            }
+            if sum_of_coupling {
+                change_sets.push(changed_files);
+            }
+            if self.grouper.is_some() {
+                group_changes.push((credits, group_files));
             }
         }
 
-        Ok(file_stats
+        // グループは、ファイルの結果に含まれるファイルの変更だけを集計する
+        if let Some(grouper) = &self.grouper {
+            for (credits, files) in group_changes {
+                let mut changed_groups: HashSet<String> = HashSet::new();
+                for (file_path, lines_added, lines_deleted) in files {
+                    let deleted = file_stats
+                        .get(&file_path)
+                        .is_some_and(|stats| stats.deleted_at.is_some());
+                    if deleted && !self.options.include_deleted {
+                        continue;
+                    }
+                    for group in grouper.groups_of(&file_path) {
+                        changed_groups.insert(group.clone());
+                        let stats = group_stats.entry(group).or_default();
+                        stats.files.insert(file_path.clone());
+                        stats.stats.lines_added += lines_added as u64;
+                        stats.stats.lines_deleted += lines_deleted as u64;
+                    }
+                }
+
+                for group in changed_groups {
+                    if let Some(stats) = group_stats.get_mut(&group) {
+                        stats.stats.revisions += 1;
+                        stats.stats.add_credits(&credits);
+                    }
+                }
+            }
+        }
+
+        if sum_of_coupling {
+            // 変更の結合度と同じく、削除されたファイルを除いたコミットごとの変更から計算する
+            if !self.options.include_deleted {
+                for files in &mut change_sets {
+                    files.retain(|path| {
+                        file_stats
+                            .get(path)
+                            .is_some_and(|stats| stats.deleted_at.is_none())
+                    });
+                }
+            }
+            let coupling_options = CouplingOptions {
+                max_changeset_size: self.options.sum_of_coupling_max_changeset_size,
+                ..Default::default()
+            };
+            let mut sums = coupling::sum_of_coupling(&change_sets, &coupling_options);
+            for (path, stats) in file_stats.iter_mut() {
+                stats.sum_of_coupling = Some(sums.remove(path).unwrap_or(0));
+            }
+        }
+
+        let mut file_stats: Vec<(String, FileStats)> = file_stats
             .into_iter()
-            .map(|(path, stats)| stats.into_metrics(path))
-            .collect())
+            .filter(|(_, stats)| stats.revisions > 0)
+            .filter(|(_, stats)| self.options.include_deleted || stats.deleted_at.is_none())
+            .collect();
+        if self.options.complexity || self.options.score_weights.uses_complexity() {
+            let mut complexity = self.repo.code_complexity(
+                file_stats
+                    .iter()
+                    .filter(|(_, stats)| stats.deleted_at.is_none())
+                    .map(|(path, _)| path.as_str()),
+            )?;
+            for (path, stats) in &mut file_stats {
+                stats.complexity = complexity.remove(path.as_str());
+            }
+        }
+        let languages = language_metrics(&file_stats);
+        let files = file_stats
+            .into_iter()
+            .map(|(path, stats)| {
+                let mut metrics = stats.into_metrics(path, &self.options.score_weights);
+                metrics.package =
+                    package_of(&self.packages, &metrics.path).map(|package| package.name.clone());
+                metrics
+            })
+            .collect();
+        let groups = group_stats
+            .into_iter()
+            .map(|(group, stats)| stats.into_metrics(group, &self.options.score_weights))
+            .collect();
+
+        Ok(AnalysisReport {
+            files,
+            skipped_commits: history.skipped,
+            diagnostics: history.diagnostics,
+            languages,
+            groups,
+            packages: self.packages.clone(),
+        })
+    }
+
+    /// 同じ変更セットで一緒に変更される傾向のあるファイルの組（変更の結合度）を計算します
+    ///
+    /// 分析対象のコミットやファイルは`analyze`と同じ設定で選択し、リネームされたファイルは
+    /// `follow_renames`に従って現在のパスに集約します。`include_deleted`が`false`の場合、
+    /// 分析期間の終了時点で削除されているファイルは結果に含めません。
+    /// コミットは`change_set`で指定された単位の変更セットにまとめてから結合度を計算します。
+    ///
+    /// # 戻り値
+    ///
+    /// しきい値を満たすファイルの組を、結合度の高い順に返します。
+    ///
+    /// # エラー
+    ///
+    /// `analyze`と同様の場合に加え、チケットのパターンが不正な場合にエラーを返します
+    pub fn analyze_coupling(
+        &self,
+        options: &CouplingOptions,
+    ) -> Result<Vec<FileCoupling>, AnalyzerError> {
+        let (commits, merged_commits) = self.coupling_commits(options)?;
+        let change_sets = coupling::change_sets(commits, options, &merged_commits)?;
+        Ok(coupling::file_couplings(&change_sets, options))
+    }
+
+    /// 複数のリポジトリをまとめて分析し、リポジトリをまたいで一緒に変更される傾向のあるファイルの組を計算します
+    ///
+    /// 各リポジトリのコミットは、それぞれのアナライザーの設定で`analyze_coupling`と同様に選択します。
+    /// コミットは`change_set`で指定された単位でリポジトリをまたいで変更セットにまとめるため、
+    /// 同じチケットを参照するコミット（`ticket`）や、指定された分数以内の間隔で続くコミット
+    /// （`window:N`、`author:N`）が一緒に変更されたものとして数えられます。
+    ///
+    /// # 引数
+    ///
+    /// - `repositories`: 結果でリポジトリを識別する名前と、そのリポジトリのアナライザー
+    /// - `options`: 変更の結合度の分析設定
+    ///
+    /// # 戻り値
+    ///
+    /// しきい値を満たす、異なるリポジトリのファイルの組を結合度の高い順に返します。
+    /// 各組の`repository`と`coupled_repository`にはリポジトリの名前が入ります。
+    ///
+    /// # エラー
+    ///
+    /// `analyze_coupling`と同様の場合に加え、リポジトリの名前が重複している場合にエラーを返します
+    pub fn analyze_cross_repository_coupling(
+        repositories: &[(String, HotspotAnalyzer)],
+        options: &CouplingOptions,
+    ) -> Result<Vec<FileCoupling>, AnalyzerError> {
+        let mut names = HashSet::new();
+        let mut changes = Vec::with_capacity(repositories.len());
+        for (name, analyzer) in repositories {
+            if !names.insert(name) {
+                return Err(AnalyzerError::InvalidOption(format!(
+                    "Duplicate repository name: {}",
+                    name
+                )));
+            }
+            let (commits, merged_commits) = analyzer.coupling_commits(options)?;
+            changes.push(coupling::RepositoryChanges {
+                name: name.clone(),
+                commits,
+                merged_commits,
+            });
+        }
+        coupling::cross_repository_couplings(changes, options)
     }
 }
 
+impl HotspotAnalyzer {
+    /// 変更の結合度の計算に使用する、コミットごとに変更されたファイルを返します
+    ///
+    /// リネームされたファイルは`follow_renames`に従って現在のパスに集約し、`include_deleted`が
+    /// `false`の場合は分析期間の終了時点で削除されているファイルを除きます。
+    /// マージ単位の変更セットを使用する場合は、コミットとそれを取り込んだマージコミットの対応も返します。
+    fn coupling_commits(
+        &self,
+        options: &CouplingOptions,
+    ) -> Result<(Vec<coupling::CommitChanges>, HashMap<String, String>), AnalyzerError> {
+        let (since, until) = self.analysis_period()?;
+        let history = self.repo.get_commits_since(since, until)?;
+
+        let mut resolver = PathResolver::new(self.options.follow_renames);
+        // ファイルごとに最初に現れる（最も新しい）変更が削除であれば、そのファイルは削除されている
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut deleted: HashSet<String> = HashSet::new();
+        let mut commits = Vec::with_capacity(history.commits.len());
+        for commit in history.commits {
+            let mut files = Vec::with_capacity(commit.files.len());
+            for change in commit.files {
+                let (path, removed_path) = resolver.resolve(change.path, change.old_path);
+                if let Some(removed_path) = removed_path {
+                    if seen.insert(removed_path.clone()) {
+                        deleted.insert(removed_path);
+                    }
+                }
+                if seen.insert(path.clone()) && change.deleted {
+                    deleted.insert(path.clone());
+                }
+                files.push(path);
+            }
+            commits.push(coupling::CommitChanges {
+                id: commit.id,
+                author: commit.author,
+                timestamp: commit.timestamp,
+                message: commit.message,
+                files,
+            });
+        }
+
+        if !self.options.include_deleted {
+            for commit in &mut commits {
+                commit.files.retain(|path| !deleted.contains(path));
+            }
+        }
+
+        let merged_commits = match options.change_set {
+            ChangeSetKind::Merge => self.repo.merged_commits(since)?,
+            _ => HashMap::new(),
+        };
+        Ok((commits, merged_commits))
+    }
+
+    /// 分析対象期間の開始日時と終了日時を返します
+    ///
+    /// 終了日時は`until`、省略時は基準日時（`as_of`、省略時は現在時刻）です。
+    /// 開始日時は`since`、省略時は終了日時から`time_window_days`日前です。
+    ///
+    /// # エラー
+    ///
+    /// 開始日時が終了日時より後の場合にエラーを返します
+    pub fn analysis_period(&self) -> Result<(DateTime<Utc>, DateTime<Utc>), AnalyzerError> {
+        let until = self
+            .options
+            .until
+            .unwrap_or_else(|| self.options.as_of.unwrap_or_else(Utc::now));
+        let since = self
+            .options
+            .since
+            .unwrap_or_else(|| until - chrono::Duration::days(self.options.time_window_days));
+        if since > until {
+            return Err(AnalyzerError::InvalidOption(format!(
+                "The start of the analysis period ({}) is after its end ({})",
+                since.format("%Y-%m-%d %H:%M:%S"),
+                until.format("%Y-%m-%d %H:%M:%S")
+            )));
+        }
+        Ok((since, until))
+    }
+
+    /// コミットに関わった開発者と、それぞれに与える貢献度を返します
+    ///
+    /// 作成者には1コミット分の貢献を与えます。共同作成者には`coauthor_credit`に従い、
+    /// 1コミット分（`Full`）または作成者と等分した貢献（`Fractional`）を与えます。
+    fn author_credits(&self, commit: &git::CommitInfo) -> Vec<(String, f64)> {
+        let credit = match self.options.coauthor_credit {
+            CoauthorCredit::Full => 1.0,
+            CoauthorCredit::Fractional => 1.0 / (commit.co_authors.len() + 1) as f64,
+        };
+
+        std::iter::once(&commit.author)
+            .chain(&commit.co_authors)
+            .map(|author| (author.clone(), credit))
+            .collect()
+    }
+}
+
+/// 変更されたファイルのパスを、リネームを追跡して分析期間の終了時点のパスに解決する構造体
+///
+/// コミットを新しい順に渡すことで、リネームを記録した後に現れる古いパスへの変更を
+/// 現在のパスへ集約できます。
+///
+/// # フィールド
+///
+/// - `follow_renames`: リネームを追跡するかどうか
+/// - `renamed_paths`: リネーム前のパスから現在のパスへの対応表
+struct PathResolver {
+    follow_renames: bool,
+    renamed_paths: HashMap<String, String>,
+}
+
+impl PathResolver {
+    fn new(follow_renames: bool) -> Self {
+        Self {
+            follow_renames,
+            renamed_paths: HashMap::new(),
+        }
+    }
+
+    /// 変更されたファイルのパスを解決します
+    ///
+    /// 解決したパスと、リネームを追跡しない場合にこの変更で削除されたものとして扱う
+    /// 変更前のパスを返します。
+    fn resolve(&mut self, path: String, old_path: Option<String>) -> (String, Option<String>) {
+        if !self.follow_renames {
+            return (path, old_path);
+        }
+
+        let current = resolve_renamed_path(&self.renamed_paths, path);
+        if let Some(old_path) = old_path {
+            if old_path != current {
+                self.renamed_paths.insert(old_path, current.clone());
+            }
+        }
+        (current, None)
+    }
+}
+
+/// リネームの対応表をたどり、パスを現在のパスに解決します
+///
+/// 同じパスを行き来するリネームで無限ループにならないよう、
+/// たどる回数は対応表の要素数までに制限します。
+fn resolve_renamed_path(renamed_paths: &HashMap<String, String>, path: String) -> String {
+    let mut current = path;
+    for _ in 0..renamed_paths.len() {
+        match renamed_paths.get(&current) {
+            Some(next) if *next != current => current = next.clone(),
+            _ => break,
+        }
+    }
+    current
+}
+
+/// ファイルごとの統計情報を言語ごとに集計します
+///
+/// 結果はファイル数の多い順（同数の場合は言語名の順）に並べます。
+fn language_metrics(file_stats: &[(String, FileStats)]) -> Vec<LanguageMetrics> {
+    let mut by_language: HashMap<&str, (LanguageMetrics, HashSet<&str>)> = HashMap::new();
+    for (path, stats) in file_stats {
+        let Some(language) = language_of(path) else {
+            continue;
+        };
+        let (metrics, authors) = by_language.entry(language).or_insert_with(|| {
+            let metrics = LanguageMetrics {
+                language: language.to_string(),
+                files: 0,
+                revisions: 0,
+                author_count: 0,
+                lines_added: 0,
+                lines_deleted: 0,
+                churn: 0,
+            };
+            (metrics, HashSet::new())
+        });
+        metrics.files += 1;
+        metrics.revisions += stats.revisions;
+        metrics.lines_added += stats.lines_added;
+        metrics.lines_deleted += stats.lines_deleted;
+        authors.extend(stats.authors.iter().map(String::as_str));
+    }
+
+    let mut languages: Vec<LanguageMetrics> = by_language
+        .into_values()
+        .map(|(mut metrics, authors)| {
+            metrics.author_count = authors.len() as u32;
+            metrics.churn = metrics.lines_added + metrics.lines_deleted;
+            metrics
+        })
+        .collect();
+    languages.sort_by(|a, b| b.files.cmp(&a.files).then(a.language.cmp(&b.language)));
+    languages
+}
+
 /// ファイルごとの統計情報を収集する内部構造体
 ///
 /// # フィールド
 ///
 /// - `revisions`: ファイルの変更回数
 /// - `authors`: ファイルを変更した開発者のセット
-/// - `author_commits`: 開発者ごとのコミット回数
+/// - `author_commits`: 開発者ごとのコミット回数（共同作成者の貢献度を含む）
+/// - `lines_added`: 追加された行数の合計
+/// - `lines_deleted`: 削除された行数の合計
+/// - `sum_of_coupling`: 各コミットで一緒に変更された他のファイルの数の合計。計算しない場合は`None`
+/// - `complexity`: 分析対象のリビジョンにおけるファイルの規模と複雑さ。計算しない場合は`None`
+/// - `deleted_at`: 分析期間の終了時点で削除されている場合、削除されたコミットの日時
 #[derive(Default)]
 struct FileStats {
     revisions: u32,
     authors: HashSet<String>,
-    author_commits: HashMap<String, u32>,
+    author_commits: HashMap<String, f64>,
+    lines_added: u64,
+    lines_deleted: u64,
+    sum_of_coupling: Option<u64>,
+    complexity: Option<CodeComplexity>,
+    deleted_at: Option<DateTime<Utc>>,
 }
 
 impl FileStats {
+    /// コミットに関わった開発者と、それぞれの貢献度を記録します
+    fn add_credits(&mut self, credits: &[(String, f64)]) {
+        for (author, credit) in credits {
+            self.authors.insert(author.clone());
+            *self.author_commits.entry(author.clone()).or_insert(0.0) += credit;
+        }
+    }
+
     /// 収集した統計情報からメトリクスを計算します
     ///
     /// # 引数
     ///
     /// - `path`: 対象ファイルのパス
+    /// - `weights`: スコアに追加で反映する指標の重み
     ///
     /// # 戻り値
     ///
     /// 計算された`FileMetrics`インスタンスを返します
-    fn into_metrics(self, path: String) -> FileMetrics {
-        let total_commits: u32 = self.author_commits.values().sum();
+    fn into_metrics(self, path: String, weights: &ScoreWeights) -> FileMetrics {
+        let total_commits: f64 = self.author_commits.values().sum();
 
-        let (main_contributor_percentage, knowledge_distribution) = if total_commits > 0 {
-            let max_author_commits = self.author_commits.values().max().unwrap_or(&0);
-            let percentage = (*max_author_commits as f64 / total_commits as f64) * 100.0;
+        let (main_contributor_percentage, knowledge_distribution) = if total_commits > 0.0 {
+            let max_author_commits = self.author_commits.values().copied().fold(0.0, f64::max);
+            let percentage = (max_author_commits / total_commits) * 100.0;
             let distribution = 1.0 - (percentage / 100.0);
             (percentage, distribution)
         } else {
             (0.0, 0.0)
         };
 
+        let churn = self.lines_added + self.lines_deleted;
+
         let complexity_factor = (self.authors.len() as f64).sqrt();
-        let hotspot_score = self.revisions as f64 * complexity_factor * knowledge_distribution;
+        let hotspot_score = self.revisions as f64
+            * complexity_factor
+            * knowledge_distribution
+            * weighted_factor(churn as f64, weights.churn)
+            * weighted_factor(
+                self.sum_of_coupling.unwrap_or(0) as f64,
+                weights.sum_of_coupling,
+            )
+            * self.complexity.map_or(1.0, |complexity| {
+                weighted_factor(complexity.lines_of_code as f64, weights.lines_of_code)
+                    * weighted_factor(complexity.indentation_complexity, weights.complexity)
+                    * weighted_factor(
+                        complexity.cyclomatic_complexity.unwrap_or(0) as f64,
+                        weights.cyclomatic_complexity,
+                    )
+            });
 
         FileMetrics {
             path,
@@ -149,12 +671,70 @@ impl FileStats {
             author_count: self.authors.len() as u32,
             main_contributor_percentage,
             knowledge_distribution,
+            lines_added: self.lines_added,
+            lines_deleted: self.lines_deleted,
+            churn,
+            sum_of_coupling: self.sum_of_coupling,
+            lines_of_code: self.complexity.map(|complexity| complexity.lines_of_code),
+            complexity: self
+                .complexity
+                .map(|complexity| complexity.indentation_complexity),
+            cyclomatic_complexity: self
+                .complexity
+                .and_then(|complexity| complexity.cyclomatic_complexity),
+            package: None,
+            deleted_at: self.deleted_at,
         }
     }
 }
 
+/// グループごとの統計情報を収集する内部構造体
+///
+/// # フィールド
+///
+/// - `stats`: グループ内のファイルを変更したコミットの統計情報
+/// - `files`: 変更されたグループ内のファイル
+#[derive(Default)]
+struct GroupStats {
+    stats: FileStats,
+    files: HashSet<String>,
+}
+
+impl GroupStats {
+    /// 収集した統計情報から、ファイルと同じ計算方法でグループのメトリクスを計算します
+    fn into_metrics(self, group: String, weights: &ScoreWeights) -> GroupMetrics {
+        let files = self.files.len() as u32;
+        let metrics = self.stats.into_metrics(group, weights);
+
+        GroupMetrics {
+            group: metrics.path,
+            hotspot_score: metrics.hotspot_score,
+            files,
+            revisions: metrics.revisions,
+            author_count: metrics.author_count,
+            main_contributor_percentage: metrics.main_contributor_percentage,
+            knowledge_distribution: metrics.knowledge_distribution,
+            lines_added: metrics.lines_added,
+            lines_deleted: metrics.lines_deleted,
+            churn: metrics.churn,
+        }
+    }
+}
+
+/// 指標の値を重み付きの係数に変換します
+///
+/// 値の大きさによる影響を抑えるため対数を取り、`(1 + ln(1 + value)) ^ weight`を返します。
+/// 重みが0の場合は常に1となり、スコアに影響しません。
+fn weighted_factor(value: f64, weight: f64) -> f64 {
+    if weight == 0.0 {
+        return 1.0;
+    }
+    (1.0 + value.max(0.0).ln_1p()).powf(weight)
+}
+
 #[cfg(test)]
 mod tests {
+    use super::test_utils::{commit_files, commit_files_at};
     use super::*;
     use git2::{Repository, Signature};
     use std::collections::HashMap;
@@ -166,17 +746,16 @@ mod tests {
     fn test_file_stats_into_metrics() {
         let mut stats = FileStats {
             revisions: 10,
-            authors: HashSet::new(),
-            author_commits: HashMap::new(),
+            ..Default::default()
         };
 
         // 開発者の貢献を追加
         stats.authors.insert("dev1".to_string());
         stats.authors.insert("dev2".to_string());
-        stats.author_commits.insert("dev1".to_string(), 7);
-        stats.author_commits.insert("dev2".to_string(), 3);
+        stats.author_commits.insert("dev1".to_string(), 7.0);
+        stats.author_commits.insert("dev2".to_string(), 3.0);
 
-        let metrics = stats.into_metrics("test.rs".to_string());
+        let metrics = stats.into_metrics("test.rs".to_string(), &ScoreWeights::default());
 
         assert_eq!(metrics.path, "test.rs");
         assert_eq!(metrics.revisions, 10);
@@ -196,7 +775,7 @@ mod tests {
     #[test]
     fn test_empty_file_stats() {
         let stats = FileStats::default();
-        let metrics = stats.into_metrics("empty.rs".to_string());
+        let metrics = stats.into_metrics("empty.rs".to_string(), &ScoreWeights::default());
 
         assert_eq!(metrics.revisions, 0);
         assert_eq!(metrics.author_count, 0);
@@ -248,6 +827,684 @@ mod tests {
         Ok((temp_dir, repo))
     }
 
+    const RENAMED_CONTENT: &str =
+        "fn main() {\n    let a = 1;\n    let b = 2;\n    println!(\"{}\", a + b);\n}\n";
+
+    // src/foo.rs を作成・変更した後に src/core/foo.rs へ移動するリポジトリを作成します
+    fn create_renamed_repo() -> Result<(TempDir, Repository), git2::Error> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[("src/foo.rs", Some(RENAMED_CONTENT))],
+            "Add foo",
+        )?;
+        commit_files(
+            &repo,
+            "bob",
+            &[("src/foo.rs", Some(&format!("{}// bob\n", RENAMED_CONTENT)))],
+            "Update foo",
+        )?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                ("src/foo.rs", None),
+                (
+                    "src/core/foo.rs",
+                    Some(&format!("{}// bob\n", RENAMED_CONTENT)),
+                ),
+            ],
+            "Move foo",
+        )?;
+        commit_files(
+            &repo,
+            "carol",
+            &[(
+                "src/core/foo.rs",
+                Some(&format!("{}// bob\n// carol\n", RENAMED_CONTENT)),
+            )],
+            "Update moved foo",
+        )?;
+        Ok((temp_dir, repo))
+    }
+
+    #[test]
+    fn test_analyze_follows_renames() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = create_renamed_repo()?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["src/**/*.rs".to_string()],
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+
+        assert_eq!(result.len(), 1);
+        let metrics = &result[0];
+        assert_eq!(metrics.path, "src/core/foo.rs");
+        assert_eq!(metrics.revisions, 4);
+        assert_eq!(metrics.author_count, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_without_following_renames() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = create_renamed_repo()?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["src/**/*.rs".to_string()],
+            follow_renames: false,
+            include_deleted: true,
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+
+        let mut paths: Vec<_> = result.iter().map(|m| m.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["src/core/foo.rs", "src/foo.rs"]);
+
+        // リネーム前のパスはリネームしたコミットで削除されたものとして扱う
+        let old = result.iter().find(|m| m.path == "src/foo.rs").unwrap();
+        assert!(old.deleted_at.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_excludes_deleted_files() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        let base = Utc::now() - chrono::Duration::days(3);
+        let at = |days: i64| base + chrono::Duration::days(days);
+        commit_files_at(
+            &repo,
+            "alice",
+            &[
+                ("a.rs", Some("a")),
+                ("b.rs", Some("b")),
+                ("c.rs", Some("c")),
+            ],
+            "Add files",
+            at(0),
+        )?;
+        commit_files_at(&repo, "bob", &[("b.rs", Some("b2"))], "Update b", at(1))?;
+        commit_files_at(
+            &repo,
+            "alice",
+            &[("b.rs", None), ("c.rs", None)],
+            "Remove b and c",
+            at(2),
+        )?;
+        // 削除後に再作成されたファイルは存在するものとして扱う
+        commit_files_at(&repo, "bob", &[("c.rs", Some("c2"))], "Restore c", at(3))?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.rs".to_string()],
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options.clone())?.analyze()?;
+        let mut paths: Vec<_> = result.iter().map(|m| m.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["a.rs", "c.rs", "test.rs"]);
+        assert!(result.iter().all(|m| m.deleted_at.is_none()));
+
+        let options = AnalyzerOptions {
+            include_deleted: true,
+            ..options
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+        let deleted = result.iter().find(|m| m.path == "b.rs").unwrap();
+        // 削除したコミットも変更として数える
+        assert_eq!(deleted.revisions, 3);
+        assert_eq!(
+            deleted.deleted_at.map(|t| t.timestamp()),
+            Some(at(2).timestamp())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_line_churn() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "test2",
+            &[(
+                "test.rs",
+                Some("fn main() {\n    println!(\"Hello\");\n}\n"),
+            )],
+            "Reformat",
+        )?;
+
+        let analyzer =
+            HotspotAnalyzer::new(temp_dir.path(), 30, vec!["*.rs".to_string()], vec![], false)?;
+        let result = analyzer.analyze()?;
+
+        assert_eq!(result.len(), 1);
+        let metrics = &result[0];
+        // 初期コミットで1行追加、2回目のコミットで1行削除・3行追加
+        assert_eq!(metrics.lines_added, 4);
+        assert_eq!(metrics.lines_deleted, 1);
+        assert_eq!(metrics.churn, 5);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_churn_weight_in_score() {
+        let stats = || {
+            let mut stats = FileStats {
+                revisions: 4,
+                lines_added: 90,
+                lines_deleted: 10,
+                ..Default::default()
+            };
+            stats.authors.insert("dev1".to_string());
+            stats.authors.insert("dev2".to_string());
+            stats.author_commits.insert("dev1".to_string(), 2.0);
+            stats.author_commits.insert("dev2".to_string(), 2.0);
+            stats
+        };
+
+        let base = stats().into_metrics("a.rs".to_string(), &ScoreWeights::default());
+        let weighted = stats().into_metrics(
+            "a.rs".to_string(),
+            &ScoreWeights {
+                churn: 1.0,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(base.churn, 100);
+        let expected = base.hotspot_score * (1.0 + 100.0_f64.ln_1p());
+        assert!((weighted.hotspot_score - expected).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_analyze_sum_of_coupling() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                ("src/a.rs", Some("// a\n")),
+                ("src/b.rs", Some("// b\n")),
+                ("src/c.rs", Some("// c\n")),
+            ],
+            "Add files",
+        )?;
+        commit_files(
+            &repo,
+            "bob",
+            &[("src/a.rs", Some("// a2\n")), ("src/b.rs", Some("// b2\n"))],
+            "Update a and b",
+        )?;
+        commit_files(&repo, "bob", &[("src/a.rs", Some("// a3\n"))], "Update a")?;
+
+        let analyze = |options: AnalyzerOptions| -> Result<Vec<FileMetrics>, AnalyzerError> {
+            let options = AnalyzerOptions {
+                include_patterns: vec!["src/**/*.rs".to_string()],
+                ..options
+            };
+            let mut result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+            result.sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(result)
+        };
+
+        // 指定しない場合は計算しない
+        let base = analyze(AnalyzerOptions::default())?;
+        assert!(base.iter().all(|m| m.sum_of_coupling.is_none()));
+
+        let result = analyze(AnalyzerOptions {
+            sum_of_coupling: true,
+            ..Default::default()
+        })?;
+        let sums: Vec<_> = result.iter().map(|m| m.sum_of_coupling).collect();
+        assert_eq!(sums, vec![Some(3), Some(3), Some(2)]);
+
+        // スコアの重みを指定した場合は常に計算し、スコアに反映する
+        let weighted = analyze(AnalyzerOptions {
+            score_weights: ScoreWeights {
+                sum_of_coupling: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        })?;
+        assert_eq!(weighted[0].sum_of_coupling, Some(3));
+        let expected = base[0].hotspot_score * (1.0 + 3.0_f64.ln_1p());
+        assert!((weighted[0].hotspot_score - expected).abs() < 0.001);
+
+        // 削除されたファイルと、変更の結合度の上限より多くのファイルを変更したコミットは数えない
+        commit_files(
+            &repo,
+            "alice",
+            &[("src/a.rs", Some("// a4\n")), ("src/d.rs", Some("// d\n"))],
+            "Add d",
+        )?;
+        commit_files(&repo, "alice", &[("src/d.rs", None)], "Remove d")?;
+        let many: Vec<(String, String)> = (0..31)
+            .map(|i| (format!("src/gen/{}.rs", i), format!("// {}\n", i)))
+            .collect();
+        let mut files: Vec<(&str, Option<&str>)> = many
+            .iter()
+            .map(|(path, content)| (path.as_str(), Some(content.as_str())))
+            .collect();
+        files.push(("src/a.rs", Some("// a5\n")));
+        commit_files(&repo, "alice", &files, "Generate files")?;
+        let result = analyze(AnalyzerOptions {
+            sum_of_coupling: true,
+            ..Default::default()
+        })?;
+        assert_eq!(result[0].path, "src/a.rs");
+        assert_eq!(result[0].sum_of_coupling, Some(3));
+
+        // 上限を外すと、大量のファイルを変更したコミットも数える
+        let result = analyze(AnalyzerOptions {
+            sum_of_coupling: true,
+            sum_of_coupling_max_changeset_size: None,
+            ..Default::default()
+        })?;
+        assert_eq!(result[0].sum_of_coupling, Some(3 + 31));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_complexity() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        let first = commit_files(
+            &repo,
+            "alice",
+            &[
+                ("src/a.rs", Some("fn a() {}\n")),
+                ("src/old.rs", Some("fn old() {}\n")),
+            ],
+            "Add files",
+        )?;
+        commit_files(
+            &repo,
+            "bob",
+            &[
+                (
+                    "src/a.rs",
+                    Some("fn a() {\n    if x {\n        y();\n    }\n}\n"),
+                ),
+                ("src/old.rs", None),
+            ],
+            "Nest a and remove old",
+        )?;
+
+        let analyze = |options: AnalyzerOptions| -> Result<Vec<FileMetrics>, AnalyzerError> {
+            let options = AnalyzerOptions {
+                include_patterns: vec!["src/**/*.rs".to_string()],
+                include_deleted: true,
+                ..options
+            };
+            let mut result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+            result.sort_by(|a, b| a.path.cmp(&b.path));
+            Ok(result)
+        };
+
+        // 指定しない場合は計算しない
+        let base = analyze(AnalyzerOptions::default())?;
+        assert!(base.iter().all(|m| m.lines_of_code.is_none()));
+
+        // 分析対象のリビジョンの内容から計算し、削除されたファイルは計算しない
+        let result = analyze(AnalyzerOptions {
+            complexity: true,
+            ..Default::default()
+        })?;
+        assert_eq!(result[0].path, "src/a.rs");
+        assert_eq!(result[0].lines_of_code, Some(5));
+        assert_eq!(result[0].complexity, Some(4.0));
+        assert_eq!(result[1].path, "src/old.rs");
+        assert_eq!(result[1].lines_of_code, None);
+
+        let result = analyze(AnalyzerOptions {
+            complexity: true,
+            revisions: vec![first.to_string()],
+            ..Default::default()
+        })?;
+        assert_eq!(result[0].lines_of_code, Some(1));
+        assert_eq!(result[0].complexity, Some(0.0));
+
+        // スコアの重みを指定した場合は常に計算し、スコアに反映する
+        let weighted = analyze(AnalyzerOptions {
+            score_weights: ScoreWeights {
+                complexity: 1.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        })?;
+        assert_eq!(weighted[0].complexity, Some(4.0));
+        let expected = base[0].hotspot_score * (1.0 + 4.0_f64.ln_1p());
+        assert!((weighted[0].hotspot_score - expected).abs() < 0.001);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_complexity_as_of() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        let base = Utc::now() - chrono::Duration::days(10);
+        let at = |days: i64| base + chrono::Duration::days(days);
+        commit_files_at(
+            &repo,
+            "alice",
+            &[("a.rs", Some("fn a() {}\n"))],
+            "Add a",
+            at(0),
+        )?;
+        commit_files_at(
+            &repo,
+            "bob",
+            &[("a.rs", Some("fn a() {\n    b();\n    c();\n}\n"))],
+            "Grow a",
+            at(5),
+        )?;
+
+        let lines_of_code = |options: AnalyzerOptions| -> Result<Option<u32>, AnalyzerError> {
+            let options = AnalyzerOptions {
+                include_patterns: vec!["a.rs".to_string()],
+                complexity: true,
+                ..options
+            };
+            let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+            Ok(result[0].lines_of_code)
+        };
+
+        assert_eq!(lines_of_code(AnalyzerOptions::default())?, Some(4));
+        // 基準日時より後に大きくなったファイルは、基準日時の時点の内容から計算する
+        assert_eq!(
+            lines_of_code(AnalyzerOptions {
+                as_of: Some(at(3)),
+                ..Default::default()
+            })?,
+            Some(1)
+        );
+        assert_eq!(
+            lines_of_code(AnalyzerOptions {
+                until: Some(at(3)),
+                ..Default::default()
+            })?,
+            Some(1)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_weighted_factor() {
+        assert_eq!(weighted_factor(1000.0, 0.0), 1.0);
+        assert_eq!(weighted_factor(0.0, 2.0), 1.0);
+        assert!(weighted_factor(10.0, 1.0) > weighted_factor(5.0, 1.0));
+        assert!(weighted_factor(10.0, 2.0) > weighted_factor(10.0, 1.0));
+    }
+
+    #[test]
+    fn test_analyze_with_mailmap() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        fs::write(
+            temp_dir.path().join(".mailmap"),
+            "Taro Yamada <taro@example.com>\nTaro Yamada <taro@example.com> <yamada-t@example.com>\n",
+        )?;
+        commit_files(&repo, "taro", &[("lib.rs", Some("// 1"))], "First")?;
+        commit_files(&repo, "yamada-t", &[("lib.rs", Some("// 2"))], "Second")?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["lib.rs".to_string()],
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options.clone())?.analyze()?;
+        assert_eq!(result[0].author_count, 1);
+        assert_eq!(result[0].main_contributor_percentage, 100.0);
+
+        // .mailmapを無効化すると別の開発者として扱われる
+        let options = AnalyzerOptions {
+            use_mailmap: false,
+            ..options
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+        assert_eq!(result[0].author_count, 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_with_author_aliases() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(&repo, "taro", &[("lib.rs", Some("// 1"))], "First")?;
+        commit_files(&repo, "yamada-t", &[("lib.rs", Some("// 2"))], "Second")?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["lib.rs".to_string()],
+            author_aliases: AuthorAliases::parse("Taro Yamada = taro, yamada-t")?,
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+        assert_eq!(result[0].author_count, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_with_co_authors() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(&repo, "alice", &[("lib.rs", Some("// 1"))], "Solo")?;
+        commit_files(
+            &repo,
+            "alice",
+            &[("lib.rs", Some("// 2"))],
+            "Pairing\n\nCo-authored-by: bob <bob@example.com>\nCo-authored-by: alice <alice@example.com>\n",
+        )?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["lib.rs".to_string()],
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options.clone())?.analyze()?;
+        assert_eq!(result[0].revisions, 2);
+        assert_eq!(result[0].author_count, 2);
+        // alice: 2, bob: 1
+        assert!((result[0].main_contributor_percentage - 200.0 / 3.0).abs() < 0.001);
+
+        let fractional = AnalyzerOptions {
+            coauthor_credit: CoauthorCredit::Fractional,
+            ..options.clone()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), fractional)?.analyze()?;
+        // alice: 1.5, bob: 0.5
+        assert!((result[0].main_contributor_percentage - 75.0).abs() < 0.001);
+
+        let disabled = AnalyzerOptions {
+            coauthor_trailers: vec![],
+            ..options
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), disabled)?.analyze()?;
+        assert_eq!(result[0].author_count, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_excludes_bot_authors() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(&repo, "alice", &[("lib.rs", Some("// 1"))], "Add lib")?;
+        commit_files(
+            &repo,
+            "dependabot[bot]",
+            &[("lib.rs", Some("// 2"))],
+            "Bump",
+        )?;
+        commit_files(&repo, "release-bot", &[("lib.rs", Some("// 3"))], "Release")?;
+        commit_files(
+            &repo,
+            "bob",
+            &[("lib.rs", Some("// 4"))],
+            "Update\n\nCo-authored-by: renovate[bot] <bot@renovateapp.com>\n",
+        )?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["lib.rs".to_string()],
+            exclude_authors: vec!["/^release-/".to_string()],
+            ..Default::default()
+        };
+        let report =
+            HotspotAnalyzer::with_options(temp_dir.path(), options.clone())?.analyze_report()?;
+        assert_eq!(report.files[0].revisions, 2);
+        // 共同作成者のボットも数えない
+        assert_eq!(report.files[0].author_count, 2);
+
+        let mut skipped: Vec<_> = report
+            .skipped_commits
+            .iter()
+            .map(|commit| commit.reason.clone())
+            .collect();
+        skipped.sort_by_key(|reason| reason.to_string());
+        assert_eq!(
+            skipped,
+            vec![
+                SkipReason::ExcludedAuthor {
+                    author: "dependabot[bot]".to_string()
+                },
+                SkipReason::ExcludedAuthor {
+                    author: "release-bot".to_string()
+                },
+            ]
+        );
+
+        // 組み込みのボット一覧を無効にする
+        let options = AnalyzerOptions {
+            exclude_bots: false,
+            ..options
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options.clone())?.analyze()?;
+        assert_eq!(result[0].revisions, 3);
+        assert_eq!(result[0].author_count, 4);
+
+        // 指定した開発者のみを分析する
+        let options = AnalyzerOptions {
+            include_authors: vec!["alice@example.com".to_string()],
+            ..options
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+        assert_eq!(result[0].revisions, 1);
+        assert_eq!(result[0].author_count, 1);
+
+        Ok(())
+    }
+
+    // 2024-01-10、2024-02-10、2024-03-10にlib.rsを変更したリポジトリを作成します
+    fn create_dated_repo() -> Result<(TempDir, Repository), git2::Error> {
+        let temp_dir = TempDir::new().unwrap();
+        let repo = Repository::init(temp_dir.path())?;
+        for (author, date) in [
+            ("alice", "2024-01-10"),
+            ("bob", "2024-02-10"),
+            ("carol", "2024-03-10"),
+        ] {
+            let time = parse_date(date, Utc::now()).unwrap();
+            commit_files_at(&repo, author, &[("lib.rs", Some(date))], date, time)?;
+        }
+        Ok((temp_dir, repo))
+    }
+
+    #[test]
+    fn test_analyze_as_of() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = create_dated_repo()?;
+        let as_of = parse_date("2024-02-15", Utc::now())?;
+
+        let options = AnalyzerOptions {
+            time_window_days: 30,
+            as_of: Some(as_of),
+            ..Default::default()
+        };
+        let analyzer = HotspotAnalyzer::with_options(temp_dir.path(), options)?;
+
+        let (since, until) = analyzer.analysis_period()?;
+        assert_eq!(since, as_of - chrono::Duration::days(30));
+        assert_eq!(until, as_of);
+
+        // 基準日時より後のコミットと期間より前のコミットは含まれない
+        let result = analyzer.analyze()?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].revisions, 1);
+        assert_eq!(result[0].author_count, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_since_until() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, _repo) = create_dated_repo()?;
+
+        let options = AnalyzerOptions {
+            since: Some(parse_date("2024-01-01", Utc::now())?),
+            until: Some(parse_date("2024-02-28", Utc::now())?),
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+        assert_eq!(result[0].revisions, 2);
+
+        // 開始日時を省略した場合は、終了日時から期間の日数だけ遡る
+        let until = parse_date("2024-02-28", Utc::now())?;
+        let options = AnalyzerOptions {
+            time_window_days: 90,
+            until: Some(until),
+            ..Default::default()
+        };
+        let analyzer = HotspotAnalyzer::with_options(temp_dir.path(), options)?;
+        assert_eq!(
+            analyzer.analysis_period()?,
+            (until - chrono::Duration::days(90), until)
+        );
+        assert_eq!(analyzer.analyze()?[0].revisions, 2);
+
+        // 開始日時が終了日時より後の場合はエラー
+        let options = AnalyzerOptions {
+            since: Some(parse_date("2030-01-01", Utc::now())?),
+            until: Some(parse_date("2020-01-01", Utc::now())?),
+            ..Default::default()
+        };
+        let analyzer = HotspotAnalyzer::with_options(temp_dir.path(), options)?;
+        assert!(matches!(
+            analyzer.analyze(),
+            Err(AnalyzerError::InvalidOption(_))
+        ));
+
+        // 期間内にコミットがない場合は空
+        let options = AnalyzerOptions {
+            as_of: Some(parse_date("2023-12-31", Utc::now())?),
+            ..Default::default()
+        };
+        let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+        assert!(result.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_renamed_path() {
+        let mut renamed_paths = HashMap::new();
+        renamed_paths.insert("a.rs".to_string(), "b.rs".to_string());
+        renamed_paths.insert("b.rs".to_string(), "c.rs".to_string());
+
+        assert_eq!(
+            resolve_renamed_path(&renamed_paths, "a.rs".to_string()),
+            "c.rs"
+        );
+        assert_eq!(
+            resolve_renamed_path(&renamed_paths, "x.rs".to_string()),
+            "x.rs"
+        );
+
+        // 循環した対応表でも停止すること
+        renamed_paths.insert("c.rs".to_string(), "a.rs".to_string());
+        let resolved = resolve_renamed_path(&renamed_paths, "a.rs".to_string());
+        assert!(["a.rs", "b.rs", "c.rs"].contains(&resolved.as_str()));
+    }
+
     #[test]
     fn test_analyzer_initialization() -> Result<(), Box<dyn std::error::Error>> {
         let (temp_dir, _) = create_test_repo()?;
@@ -358,6 +1615,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_file_stats_metrics_calculation() {
         let mut stats = FileStats::default();
 
@@ -367,11 +1625,11 @@ mod tests {
         stats.authors.insert("dev2".to_string());
         stats.authors.insert("dev3".to_string());
 
-        stats.author_commits.insert("dev1".to_string(), 5);
-        stats.author_commits.insert("dev2".to_string(), 3);
-        stats.author_commits.insert("dev3".to_string(), 2);
+        stats.author_commits.insert("dev1".to_string(), 5.0);
+        stats.author_commits.insert("dev2".to_string(), 3.0);
+        stats.author_commits.insert("dev3".to_string(), 2.0);
 
-        let metrics = stats.into_metrics("test_file.rs".to_string());
+        let metrics = stats.into_metrics("test_file.rs".to_string(), &ScoreWeights::default());
 
         assert_eq!(metrics.path, "test_file.rs");
         assert_eq!(metrics.revisions, 10);
@@ -421,10 +1679,443 @@ mod tests {
     }
 
     #[test]
+    fn test_analyze_language_metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[("app/a.py", Some("a\n")), ("app/b.py", Some("b\n"))],
+            "Add python",
+        )?;
+        commit_files(&repo, "bob", &[("app/a.py", Some("a\na\n"))], "Update a")?;
+        commit_files(&repo, "carol", &[("README.md", Some("readme\n"))], "Docs")?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.py".to_string(), "*.md".to_string()],
+            ..Default::default()
+        };
+        let report = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze_report()?;
+
+        // 言語を判定できないファイルは集計しない
+        assert_eq!(
+            report.languages,
+            vec![LanguageMetrics {
+                language: "python".to_string(),
+                files: 2,
+                revisions: 3,
+                author_count: 2,
+                lines_added: 3,
+                lines_deleted: 0,
+                churn: 3,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_group_metrics() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                ("services/api/handler.py", Some("a\n")),
+                ("services/api/routes/users.py", Some("b\n")),
+            ],
+            "Add api",
+        )?;
+        commit_files(
+            &repo,
+            "bob",
+            &[("services/api/handler.py", Some("a\na\n"))],
+            "Update handler",
+        )?;
+        commit_files(&repo, "carol", &[("setup.py", Some("c\n"))], "Add setup")?;
+
+        let analyze = |group_by: GroupBy| -> Result<Vec<GroupMetrics>, AnalyzerError> {
+            let options = AnalyzerOptions {
+                include_patterns: vec!["*.py".to_string()],
+                group_by: Some(group_by),
+                components: vec!["api=services/api/".parse()?],
+                ..Default::default()
+            };
+            let mut groups = HotspotAnalyzer::with_options(temp_dir.path(), options)?
+                .analyze_report()?
+                .groups;
+            groups.sort_by(|a, b| a.group.cmp(&b.group));
+            Ok(groups)
+        };
+
+        // 1つのコミットで複数のファイルを変更しても、グループの変更回数は1回と数える
+        let groups = analyze(GroupBy::Directory(2))?;
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| {
+                (
+                    g.group.as_str(),
+                    g.files,
+                    g.revisions,
+                    g.author_count,
+                    g.churn,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(".", 1, 1, 1, 1), ("services/api", 2, 2, 2, 3)]
+        );
+        assert!((groups[1].knowledge_distribution - 0.5).abs() < 0.001);
+
+        // どのコンポーネントにも一致しないファイルは集計しない
+        let groups = analyze(GroupBy::Component)?;
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].group, "api");
+        assert_eq!(groups[0].revisions, 2);
+
+        // ファイルの結果と同じく、削除されたファイルの変更は集計しない
+        commit_files(
+            &repo,
+            "dave",
+            &[("services/api/legacy.py", Some("d\n"))],
+            "Add legacy",
+        )?;
+        commit_files(
+            &repo,
+            "dave",
+            &[("services/api/legacy.py", None)],
+            "Remove legacy",
+        )?;
+        let groups = analyze(GroupBy::Component)?;
+        assert_eq!(
+            (groups[0].files, groups[0].revisions, groups[0].author_count),
+            (2, 2, 2)
+        );
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.py".to_string()],
+            group_by: Some(GroupBy::Component),
+            components: vec!["api=services/api/".parse()?],
+            include_deleted: true,
+            ..Default::default()
+        };
+        let groups = HotspotAnalyzer::with_options(temp_dir.path(), options)?
+            .analyze_report()?
+            .groups;
+        assert_eq!(
+            (groups[0].files, groups[0].revisions, groups[0].author_count),
+            (3, 4, 3)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_packages() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                ("go.work", Some("go 1.21\n")),
+                ("api/go.mod", Some("module example.com/api\n")),
+                ("api/main.go", Some("package main\n")),
+                ("worker/go.mod", Some("module example.com/worker\n")),
+                ("worker/job.go", Some("package worker\n")),
+                ("tools/gen.go", Some("package tools\n")),
+            ],
+            "Add modules",
+        )?;
+        commit_files(
+            &repo,
+            "bob",
+            &[("api/main.go", Some("package main\n\nfunc main() {}\n"))],
+            "Update api",
+        )?;
+
+        let analyze = |options: AnalyzerOptions| -> Result<AnalysisReport, AnalyzerError> {
+            let options = AnalyzerOptions {
+                include_patterns: vec!["*.go".to_string()],
+                ..options
+            };
+            HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze_report()
+        };
+
+        // パッケージを検出しない場合は記録しない
+        let report = analyze(AnalyzerOptions::default())?;
+        assert!(report.packages.is_empty());
+        assert!(report.files.iter().all(|m| m.package.is_none()));
+
+        let report = analyze(AnalyzerOptions {
+            detect_packages: true,
+            ..Default::default()
+        })?;
+        let mut packages: Vec<_> = report
+            .files
+            .iter()
+            .map(|m| (m.path.as_str(), m.package.as_deref()))
+            .collect();
+        packages.sort();
+        assert_eq!(
+            packages,
+            vec![
+                ("api/main.go", Some("example.com/api")),
+                ("tools/gen.go", None),
+                ("worker/job.go", Some("example.com/worker")),
+            ]
+        );
+
+        let report = analyze(AnalyzerOptions {
+            group_by: Some(GroupBy::Package),
+            ..Default::default()
+        })?;
+        let mut groups: Vec<_> = report
+            .groups
+            .iter()
+            .map(|g| (g.group.as_str(), g.revisions, g.author_count))
+            .collect();
+        groups.sort();
+        assert_eq!(
+            groups,
+            vec![("example.com/api", 2, 2), ("example.com/worker", 1, 1)]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_coupling() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                ("src/model.rs", Some("// model v1\n")),
+                ("src/old_view.rs", Some("// view v1\n")),
+                ("src/legacy.rs", Some("// legacy v1\n")),
+            ],
+            "Add files",
+        )?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                ("src/model.rs", Some("// model v2\n")),
+                ("src/old_view.rs", None),
+                ("src/view.rs", Some("// view v1\n")),
+                ("src/legacy.rs", Some("// legacy v2\n")),
+            ],
+            "Rename view",
+        )?;
+        commit_files(
+            &repo,
+            "bob",
+            &[
+                ("src/model.rs", Some("// model v3\n")),
+                ("src/view.rs", Some("// view v2\n")),
+            ],
+            "Update model and view",
+        )?;
+        commit_files(&repo, "bob", &[("src/legacy.rs", None)], "Remove legacy")?;
+
+        let analyzer = HotspotAnalyzer::with_options(
+            temp_dir.path(),
+            AnalyzerOptions {
+                include_patterns: vec!["src/**/*.rs".to_string()],
+                ..Default::default()
+            },
+        )?;
+        let options = CouplingOptions {
+            min_revisions: 1,
+            min_shared_commits: 1,
+            min_degree: 0.0,
+            max_changeset_size: None,
+            ..Default::default()
+        };
+        let couplings = analyzer.analyze_coupling(&options)?;
+
+        // リネーム前の履歴は現在のパスに集約し、削除されたファイルは含めない
+        assert_eq!(couplings.len(), 1);
+        assert_eq!(couplings[0].path, "src/model.rs");
+        assert_eq!(couplings[0].coupled_path, "src/view.rs");
+        assert_eq!(couplings[0].shared_commits, 3);
+        assert!((couplings[0].degree - 1.0).abs() < 1e-10);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_cross_repository_coupling() -> Result<(), Box<dyn std::error::Error>> {
+        let frontend_dir = TempDir::new()?;
+        let frontend = Repository::init(frontend_dir.path())?;
+        let backend_dir = TempDir::new()?;
+        let backend = Repository::init(backend_dir.path())?;
+        let base = Utc::now() - chrono::Duration::days(1);
+        let at = |minutes: i64| base + chrono::Duration::minutes(minutes);
+
+        commit_files_at(
+            &frontend,
+            "alice",
+            &[("src/api.ts", Some("// v1\n"))],
+            "PROJ-1 client",
+            at(0),
+        )?;
+        commit_files_at(
+            &backend,
+            "bob",
+            &[("src/api.rs", Some("// v1\n"))],
+            "PROJ-1 endpoint",
+            at(5),
+        )?;
+        commit_files_at(
+            &frontend,
+            "alice",
+            &[("src/api.ts", Some("// v2\n"))],
+            "PROJ-2 retry",
+            at(120),
+        )?;
+        commit_files_at(
+            &backend,
+            "bob",
+            &[("src/api.rs", Some("// v2\n"))],
+            "Fix typo",
+            at(600),
+        )?;
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["src/**/*".to_string()],
+            ..Default::default()
+        };
+        let repositories = vec![
+            (
+                "frontend".to_string(),
+                HotspotAnalyzer::with_options(frontend_dir.path(), options.clone())?,
+            ),
+            (
+                "backend".to_string(),
+                HotspotAnalyzer::with_options(backend_dir.path(), options)?,
+            ),
+        ];
+        let coupling_options = |change_set| CouplingOptions {
+            min_revisions: 1,
+            min_shared_commits: 1,
+            min_degree: 0.0,
+            max_changeset_size: None,
+            change_set,
+            ..Default::default()
+        };
+
+        // 同じチケットを参照するコミットで結び付ける
+        let couplings = HotspotAnalyzer::analyze_cross_repository_coupling(
+            &repositories,
+            &coupling_options(ChangeSetKind::Ticket),
+        )?;
+        assert_eq!(couplings.len(), 1);
+        assert_eq!(couplings[0].repository.as_deref(), Some("frontend"));
+        assert_eq!(couplings[0].path, "src/api.ts");
+        assert_eq!(couplings[0].coupled_repository.as_deref(), Some("backend"));
+        assert_eq!(couplings[0].coupled_path, "src/api.rs");
+        assert_eq!(couplings[0].shared_commits, 1);
+        assert_eq!(
+            (couplings[0].revisions, couplings[0].coupled_revisions),
+            (2, 2)
+        );
+
+        // 近い日時に行われたコミットで結び付ける
+        let couplings = HotspotAnalyzer::analyze_cross_repository_coupling(
+            &repositories,
+            &coupling_options(ChangeSetKind::Window(10)),
+        )?;
+        assert_eq!(couplings.len(), 1);
+        assert_eq!(couplings[0].shared_commits, 1);
+
+        // 名前の重複はエラー
+        let duplicated = vec![
+            (
+                "app".to_string(),
+                HotspotAnalyzer::with_options(frontend_dir.path(), AnalyzerOptions::default())?,
+            ),
+            (
+                "app".to_string(),
+                HotspotAnalyzer::with_options(backend_dir.path(), AnalyzerOptions::default())?,
+            ),
+        ];
+        assert!(HotspotAnalyzer::analyze_cross_repository_coupling(
+            &duplicated,
+            &coupling_options(ChangeSetKind::Ticket)
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_analyze_with_repository_rules() -> Result<(), Box<dyn std::error::Error>> {
+        let (temp_dir, repo) = create_test_repo()?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                (".hotspotignore", Some("legacy/\n")),
+                (".gitattributes", Some("*.pb.rs linguist-generated\n")),
+                ("legacy/old.rs", Some("// old\n")),
+                ("api/service.pb.rs", Some("// generated\n")),
+                ("src/lib.rs", Some("// lib\n")),
+            ],
+            "Add files",
+        )?;
+
+        let analyze = |options: AnalyzerOptions| -> Result<Vec<String>, AnalyzerError> {
+            let result = HotspotAnalyzer::with_options(temp_dir.path(), options)?.analyze()?;
+            let mut paths: Vec<_> = result.into_iter().map(|m| m.path).collect();
+            paths.sort();
+            Ok(paths)
+        };
+
+        let options = AnalyzerOptions {
+            include_patterns: vec!["*.rs".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(analyze(options.clone())?, vec!["src/lib.rs", "test.rs"]);
+
+        let disabled = AnalyzerOptions {
+            use_gitattributes: false,
+            use_hotspotignore: false,
+            ..options.clone()
+        };
+        assert_eq!(
+            analyze(disabled)?,
+            vec![
+                "api/service.pb.rs",
+                "legacy/old.rs",
+                "src/lib.rs",
+                "test.rs"
+            ]
+        );
+
+        // ルールは分析対象のリビジョンのツリーから読み込む
+        commit_files(
+            &repo,
+            "alice",
+            &[(".hotspotignore", Some("src/\n"))],
+            "Ignore src",
+        )?;
+        assert_eq!(analyze(options.clone())?, vec!["legacy/old.rs", "test.rs"]);
+        let options = AnalyzerOptions {
+            revisions: vec!["HEAD~1".to_string()],
+            ..options
+        };
+        assert_eq!(analyze(options)?, vec!["src/lib.rs", "test.rs"]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_file_stats_edge_cases() {
         // 空の統計
         let empty_stats = FileStats::default();
-        let metrics = empty_stats.into_metrics("empty.rs".to_string());
+        let metrics = empty_stats.into_metrics("empty.rs".to_string(), &ScoreWeights::default());
         assert_eq!(metrics.hotspot_score, 0.0);
         assert_eq!(metrics.knowledge_distribution, 0.0);
         assert_eq!(metrics.main_contributor_percentage, 0.0);
@@ -435,9 +2126,10 @@ mod tests {
         single_author_stats.authors.insert("dev1".to_string());
         single_author_stats
             .author_commits
-            .insert("dev1".to_string(), 1);
+            .insert("dev1".to_string(), 1.0);
 
-        let metrics = single_author_stats.into_metrics("single.rs".to_string());
+        let metrics =
+            single_author_stats.into_metrics("single.rs".to_string(), &ScoreWeights::default());
         assert_eq!(metrics.main_contributor_percentage, 100.0);
         assert_eq!(metrics.knowledge_distribution, 0.0);
 
@@ -446,10 +2138,10 @@ mod tests {
         equal_stats.revisions = 4;
         equal_stats.authors.insert("dev1".to_string());
         equal_stats.authors.insert("dev2".to_string());
-        equal_stats.author_commits.insert("dev1".to_string(), 2);
-        equal_stats.author_commits.insert("dev2".to_string(), 2);
+        equal_stats.author_commits.insert("dev1".to_string(), 2.0);
+        equal_stats.author_commits.insert("dev2".to_string(), 2.0);
 
-        let metrics = equal_stats.into_metrics("equal.rs".to_string());
+        let metrics = equal_stats.into_metrics("equal.rs".to_string(), &ScoreWeights::default());
         assert_eq!(metrics.main_contributor_percentage, 50.0);
         assert_eq!(metrics.knowledge_distribution, 0.5);
     }
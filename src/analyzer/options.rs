@@ -0,0 +1,218 @@
+//! 分析オプションを定義するモジュール
+//!
+//! このモジュールは、`HotspotAnalyzer`と`GitRepository`の動作を制御する
+//! 設定値をまとめた構造体を提供します。
+
+use super::author::{AuthorAliases, AuthorKey, CoauthorCredit};
+use super::coupling::{ChangeSetKind, DEFAULT_TICKET_PATTERN};
+use super::diff::MergeMode;
+use super::group::{Component, GroupBy};
+use chrono::{DateTime, Utc};
+use std::path::PathBuf;
+
+/// 共同作成者として扱うデフォルトのトレーラー
+pub const DEFAULT_COAUTHOR_TRAILERS: &[&str] = &["Co-authored-by"];
+
+/// リネーム検出で使用するデフォルトの類似度しきい値（%）
+///
+/// `git diff -M`のデフォルト値と同じ50%を使用します。
+pub const DEFAULT_RENAME_THRESHOLD: u16 = 50;
+
+/// ホットスポット分析の設定を保持する構造体
+///
+/// # フィールド
+///
+/// - `time_window_days`: 分析対象期間（日数）。`since`が指定されている場合は使用しない
+/// - `since`: 分析対象期間の開始日時
+/// - `until`: 分析対象期間の終了日時。省略した場合は`as_of`
+/// - `as_of`: 分析の基準日時。省略した場合は現在時刻
+/// - `include_patterns`: 分析対象とするファイルパターン
+/// - `exclude_patterns`: 分析から除外するファイルパターン
+/// - `use_gitignore`: 分析対象のリビジョンの`.gitignore`に一致するファイルを除外するかどうか
+/// - `use_gitattributes`: 分析対象のリビジョンの`.gitattributes`で`linguist-generated`、`linguist-vendored`、`-diff`が指定されたファイルを除外するかどうか
+/// - `use_hotspotignore`: 分析対象のリビジョンの`.hotspotignore`（gitignore形式）に一致するファイルを除外するかどうか
+/// - `merge_mode`: マージコミットの差分の計算方法
+/// - `first_parent`: 履歴を第1親のみたどるかどうか（`git log --first-parent`と同様）
+/// - `rename_threshold`: リネーム・コピー検出の類似度しきい値（%）。`None`の場合は検出しない
+/// - `renames_across_scope`: 分析対象の範囲（`paths`やインクルードパターン）の内外の間で移動したファイルもリネームとして検出するかどうか
+/// - `skip_binary`: バイナリファイルを分析から除外するかどうか
+/// - `skip_generated`: 先頭に`GENERATED_FILE_MARKERS`の目印を含む自動生成されたファイルを分析から除外するかどうか
+/// - `follow_renames`: リネームされたファイルの履歴を現在のパスに集約するかどうか
+/// - `include_deleted`: 分析期間の終了時点で削除されているファイルも結果に含めるかどうか
+/// - `score_weights`: ホットスポットスコアに追加で反映する指標の重み
+/// - `sum_of_coupling`: 各ファイルの`sum_of_coupling`を計算するかどうか。スコアの重みが指定されている場合は常に計算する
+/// - `sum_of_coupling_max_changeset_size`: `sum_of_coupling`の計算で、分析対象のファイルをこの数より多く変更したコミットを数えない。`None`の場合は制限しない
+/// - `complexity`: 分析対象のリビジョンにおける各ファイルの行数と複雑さを計算するかどうか。スコアの重みが指定されている場合は常に計算する
+/// - `group_by`: メトリクスをまとめて集計する単位。`None`の場合はファイル単位のみ
+/// - `components`: `GroupBy::Component`で使用するコンポーネントの定義
+/// - `detect_packages`: ワークスペースのパッケージを検出し、各ファイルにパッケージを記録するかどうか。`GroupBy::Package`では常に検出する
+/// - `use_mailmap`: リポジトリの`.mailmap`で開発者を正規化するかどうか
+/// - `author_key`: 開発者を名前とメールアドレスのどちらで識別するか
+/// - `author_aliases`: 開発者の別名と正規の名前の対応
+/// - `coauthor_trailers`: 共同作成者として扱うコミットメッセージのトレーラー。空の場合は共同作成者を扱わない
+/// - `coauthor_credit`: 共同作成者への貢献度の配分方法
+/// - `include_authors`: 分析に含める開発者（名前・メールアドレス、`/`で囲んだ正規表現）。空の場合は全員
+/// - `exclude_authors`: 分析から除外する開発者（名前・メールアドレス、`/`で囲んだ正規表現）
+/// - `exclude_bots`: `DEFAULT_BOT_AUTHORS`のボット・サービスアカウントを除外するかどうか
+/// - `revisions`: 分析対象のリビジョン（`v1.2.0..v1.3.0`などの範囲指定も可）。空の場合は`HEAD`
+/// - `all_branches`: 全てのローカルブランチを分析対象とするかどうか
+/// - `paths`: 分析対象とするディレクトリまたはファイル。空の場合はリポジトリ全体
+/// - `jobs`: 差分の計算に使用するスレッド数。0の場合は利用可能なCPU数
+/// - `cache`: コミットごとの差分の計算結果を`.git/hotspot-cache`にキャッシュするかどうか
+/// - `cache_dir`: キャッシュを保存するディレクトリ。指定した場合は`cache`によらずキャッシュを使用する
+/// - `max_files_per_commit`: 分析対象のファイルをこの数より多く変更したコミットを除外する
+/// - `exclude_messages`: コミットメッセージがこれらの正規表現に一致するコミットを除外する
+#[derive(Debug, Clone)]
+pub struct AnalyzerOptions {
+    pub time_window_days: i64,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub as_of: Option<DateTime<Utc>>,
+    pub include_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub use_gitignore: bool,
+    pub use_gitattributes: bool,
+    pub use_hotspotignore: bool,
+    pub merge_mode: MergeMode,
+    pub first_parent: bool,
+    pub rename_threshold: Option<u16>,
+    pub renames_across_scope: bool,
+    pub skip_binary: bool,
+    pub skip_generated: bool,
+    pub follow_renames: bool,
+    pub include_deleted: bool,
+    pub score_weights: ScoreWeights,
+    pub sum_of_coupling: bool,
+    pub sum_of_coupling_max_changeset_size: Option<usize>,
+    pub complexity: bool,
+    pub group_by: Option<GroupBy>,
+    pub components: Vec<Component>,
+    pub detect_packages: bool,
+    pub use_mailmap: bool,
+    pub author_key: AuthorKey,
+    pub author_aliases: AuthorAliases,
+    pub coauthor_trailers: Vec<String>,
+    pub coauthor_credit: CoauthorCredit,
+    pub include_authors: Vec<String>,
+    pub exclude_authors: Vec<String>,
+    pub exclude_bots: bool,
+    pub revisions: Vec<String>,
+    pub all_branches: bool,
+    pub paths: Vec<String>,
+    pub jobs: usize,
+    pub cache: bool,
+    pub cache_dir: Option<PathBuf>,
+    pub max_files_per_commit: Option<usize>,
+    pub exclude_messages: Vec<String>,
+}
+
+impl Default for AnalyzerOptions {
+    fn default() -> Self {
+        Self {
+            time_window_days: 365,
+            since: None,
+            until: None,
+            as_of: None,
+            include_patterns: Vec::new(),
+            exclude_patterns: Vec::new(),
+            use_gitignore: false,
+            use_gitattributes: true,
+            use_hotspotignore: true,
+            merge_mode: MergeMode::default(),
+            first_parent: false,
+            rename_threshold: Some(DEFAULT_RENAME_THRESHOLD),
+            renames_across_scope: false,
+            skip_binary: true,
+            skip_generated: false,
+            follow_renames: true,
+            include_deleted: false,
+            score_weights: ScoreWeights::default(),
+            sum_of_coupling: false,
+            sum_of_coupling_max_changeset_size: Some(30),
+            complexity: false,
+            group_by: None,
+            components: Vec::new(),
+            detect_packages: false,
+            use_mailmap: true,
+            author_key: AuthorKey::default(),
+            author_aliases: AuthorAliases::default(),
+            coauthor_trailers: DEFAULT_COAUTHOR_TRAILERS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            coauthor_credit: CoauthorCredit::default(),
+            include_authors: Vec::new(),
+            exclude_authors: Vec::new(),
+            exclude_bots: true,
+            revisions: Vec::new(),
+            all_branches: false,
+            paths: Vec::new(),
+            jobs: 0,
+            cache: false,
+            cache_dir: None,
+            max_files_per_commit: None,
+            exclude_messages: Vec::new(),
+        }
+    }
+}
+
+/// ホットスポットスコアに追加で反映する指標の重みを保持する構造体
+///
+/// 各指標は`(1 + ln(1 + 値)) ^ 重み`の係数としてスコアに掛け合わされます。
+/// 重みが0の場合、その指標はスコアに影響しません。
+///
+/// # フィールド
+///
+/// - `churn`: 追加・削除行数の合計（チャーン）の重み
+/// - `sum_of_coupling`: 一緒に変更されたファイルの数の合計（`sum_of_coupling`）の重み
+/// - `lines_of_code`: 分析対象のリビジョンにおける行数（`lines_of_code`）の重み
+/// - `complexity`: 分析対象のリビジョンにおけるインデントに基づく複雑さ（`complexity`）の重み
+/// - `cyclomatic_complexity`: 分析対象のリビジョンにおける循環的複雑度（`cyclomatic_complexity`）の重み
+#[derive(Debug, Clone, Default)]
+pub struct ScoreWeights {
+    pub churn: f64,
+    pub sum_of_coupling: f64,
+    pub lines_of_code: f64,
+    pub complexity: f64,
+    pub cyclomatic_complexity: f64,
+}
+
+impl ScoreWeights {
+    /// 行数と複雑さのいずれかがスコアに反映されるかどうかを返します
+    pub fn uses_complexity(&self) -> bool {
+        self.lines_of_code > 0.0 || self.complexity > 0.0 || self.cyclomatic_complexity > 0.0
+    }
+}
+
+/// 変更の結合度（同じ変更セットで一緒に変更される度合い）の分析設定を保持する構造体
+///
+/// # フィールド
+///
+/// - `min_revisions`: 分析対象とするファイルの最小の変更回数
+/// - `min_shared_commits`: 報告するファイルの組が一緒に変更された最小の回数
+/// - `min_degree`: 報告する最小の結合度（0.0-1.0）
+/// - `max_changeset_size`: この数より多くのファイルを変更した変更セットを結合度の計算から除外する。`None`の場合は制限しない
+/// - `change_set`: 一緒に変更されたとみなす変更セットの単位
+/// - `ticket_pattern`: チケット単位でコミットメッセージからチケットの識別子を抽出する正規表現
+#[derive(Debug, Clone)]
+pub struct CouplingOptions {
+    pub min_revisions: u32,
+    pub min_shared_commits: u32,
+    pub min_degree: f64,
+    pub max_changeset_size: Option<usize>,
+    pub change_set: ChangeSetKind,
+    pub ticket_pattern: String,
+}
+
+impl Default for CouplingOptions {
+    fn default() -> Self {
+        Self {
+            min_revisions: 5,
+            min_shared_commits: 2,
+            min_degree: 0.3,
+            max_changeset_size: Some(30),
+            change_set: ChangeSetKind::Commit,
+            ticket_pattern: DEFAULT_TICKET_PATTERN.to_string(),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+//! テスト用の共通ヘルパー関数を提供するモジュール
+
+use chrono::{DateTime, Utc};
+use git2::{Oid, Repository, Signature, Time};
+use std::fs;
+use std::path::Path;
+
+/// ファイルの作成・更新・削除を行い、`HEAD`にコミットします
+///
+/// 内容が`None`のファイルは削除されます。
+/// コミットの作成者のメールアドレスは`<author>@example.com`になります。
+pub(crate) fn commit_files(
+    repo: &Repository,
+    author: &str,
+    files: &[(&str, Option<&str>)],
+    message: &str,
+) -> Result<Oid, git2::Error> {
+    commit_files_at(repo, author, files, message, Utc::now())
+}
+
+/// `commit_files`と同様にコミットします。コミット日時には`time`を使用します
+pub(crate) fn commit_files_at(
+    repo: &Repository,
+    author: &str,
+    files: &[(&str, Option<&str>)],
+    message: &str,
+    time: DateTime<Utc>,
+) -> Result<Oid, git2::Error> {
+    commit_with_parents(repo, author, files, message, time, &[])
+}
+
+/// `HEAD`に`branch`をマージするコミットを作成します
+///
+/// マージ結果のファイルの内容は`files`で指定します（自動ではマージしません）。
+pub(crate) fn merge_branch(
+    repo: &Repository,
+    author: &str,
+    branch: &str,
+    files: &[(&str, Option<&str>)],
+    message: &str,
+) -> Result<Oid, git2::Error> {
+    let merged = repo
+        .find_branch(branch, git2::BranchType::Local)?
+        .get()
+        .peel_to_commit()?;
+    commit_with_parents(repo, author, files, message, Utc::now(), &[merged.id()])
+}
+
+/// `HEAD`と`extra_parents`を親とするコミットを作成します
+fn commit_with_parents(
+    repo: &Repository,
+    author: &str,
+    files: &[(&str, Option<&str>)],
+    message: &str,
+    time: DateTime<Utc>,
+    extra_parents: &[Oid],
+) -> Result<Oid, git2::Error> {
+    let workdir = repo.workdir().unwrap().to_path_buf();
+    let signature = Signature::new(
+        author,
+        &format!("{}@example.com", author),
+        &Time::new(time.timestamp(), 0),
+    )?;
+
+    let mut index = repo.index()?;
+    for (path, content) in files {
+        let full_path = workdir.join(path);
+        match content {
+            Some(content) => {
+                fs::create_dir_all(full_path.parent().unwrap()).unwrap();
+                fs::write(&full_path, content).unwrap();
+                index.add_path(Path::new(path))?;
+            }
+            None => {
+                fs::remove_file(&full_path).unwrap();
+                index.remove_path(Path::new(path))?;
+            }
+        }
+    }
+    index.write()?;
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let mut parents = match repo.head() {
+        Ok(head) => vec![head.peel_to_commit()?],
+        Err(_) => vec![],
+    };
+    for parent in extra_parents {
+        parents.push(repo.find_commit(*parent)?);
+    }
+    let parent_refs: Vec<_> = parents.iter().collect();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        message,
+        &tree,
+        &parent_refs,
+    )
+}
+
+/// ブランチを作成（既に存在する場合はそのまま）し、作業ツリーごと切り替えます
+pub(crate) fn checkout_branch(repo: &Repository, name: &str) -> Result<(), git2::Error> {
+    if repo.find_branch(name, git2::BranchType::Local).is_err() {
+        let head = repo.head()?.peel_to_commit()?;
+        repo.branch(name, &head, false)?;
+    }
+    repo.set_head(&format!("refs/heads/{}", name))?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+}
@@ -0,0 +1,399 @@
+//! モノレポのパッケージを検出するモジュール
+//!
+//! このモジュールは、分析対象のリビジョンのツリーに含まれるマニフェストから
+//! ワークスペースを構成するパッケージのルートディレクトリを検出する機能を提供します。
+//!
+//! # 検出するパッケージ
+//!
+//! - Cargo: `[workspace]`を含む`Cargo.toml`の`members`（`exclude`を除く）と、
+//!   `[package]`を含むワークスペースのルート
+//! - npm: `workspaces`を含む`package.json`の`workspaces`（配列または`packages`）
+//! - Go: `go.mod`を含む全てのディレクトリ
+//!
+//! パッケージの名前はそれぞれのマニフェストの`name`（Goは`module`）から読み込み、
+//! 読み込めない場合はディレクトリ名を使用します。
+
+use super::error::AnalyzerError;
+use super::glob::GlobSet;
+use git2::{ObjectType, Oid, Repository, Tree, TreeWalkMode, TreeWalkResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+const CARGO_MANIFEST: &str = "Cargo.toml";
+const NPM_MANIFEST: &str = "package.json";
+const GO_MANIFEST: &str = "go.mod";
+
+/// パッケージを定義するマニフェストの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackageKind {
+    Cargo,
+    Npm,
+    Go,
+}
+
+/// ワークスペースを構成するパッケージを表す構造体
+///
+/// # フィールド
+///
+/// - `name`: パッケージの名前
+/// - `root`: パッケージのルートディレクトリ（リポジトリのルートは`.`）
+/// - `kind`: パッケージを定義するマニフェストの種類
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub root: String,
+    pub kind: PackageKind,
+}
+
+impl Package {
+    /// ファイルパスがこのパッケージのディレクトリに含まれるかどうかを判定します
+    fn contains(&self, path: &str) -> bool {
+        self.root == "."
+            || path
+                .strip_prefix(self.root.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+/// ファイルパスを含むパッケージを返します
+///
+/// 入れ子になったパッケージでは、ルートディレクトリが最も深いパッケージを返します。
+pub(crate) fn package_of<'a>(packages: &'a [Package], path: &str) -> Option<&'a Package> {
+    packages
+        .iter()
+        .filter(|package| package.contains(path))
+        .max_by_key(|package| match package.root.as_str() {
+            "." => 0,
+            root => root.len(),
+        })
+}
+
+/// ツリーに含まれるマニフェストからパッケージを検出します
+///
+/// 同じディレクトリに複数の種類のマニフェストがある場合は、Cargo、npm、Goの順に優先します。
+/// パッケージはルートディレクトリの順に並べて返します。
+///
+/// # エラー
+///
+/// ツリーの走査やマニフェストの読み込みに失敗した場合にエラーを返します。
+/// マニフェストの形式が不正な場合は、そのマニフェストを無視します
+pub(crate) fn discover_packages(
+    repo: &Repository,
+    tree: &Tree,
+) -> Result<Vec<Package>, AnalyzerError> {
+    let mut manifests: BTreeMap<&str, Vec<(String, Oid)>> = BTreeMap::new();
+    tree.walk(TreeWalkMode::PreOrder, |dir, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            if let Some(name) = entry.name() {
+                for manifest in [CARGO_MANIFEST, NPM_MANIFEST, GO_MANIFEST] {
+                    if name == manifest {
+                        manifests
+                            .entry(manifest)
+                            .or_default()
+                            .push((dir.trim_end_matches('/').to_string(), entry.id()));
+                    }
+                }
+            }
+        }
+        TreeWalkResult::Ok
+    })?;
+    let manifests_of = |name: &str| manifests.get(name).cloned().unwrap_or_default();
+
+    let mut packages: BTreeMap<String, Package> = BTreeMap::new();
+    let cargo = read_manifests(repo, manifests_of(CARGO_MANIFEST), |content| {
+        toml::from_str(content).ok()
+    })?;
+    let npm = read_manifests(repo, manifests_of(NPM_MANIFEST), |content| {
+        serde_json::from_str(content).ok()
+    })?;
+
+    for package in workspace_members(&cargo, PackageKind::Cargo)
+        .into_iter()
+        .chain(workspace_members(&npm, PackageKind::Npm))
+    {
+        packages.entry(package.root.clone()).or_insert(package);
+    }
+
+    for (dir, id) in manifests_of(GO_MANIFEST) {
+        let blob = repo.find_blob(id)?;
+        let module = std::str::from_utf8(blob.content())
+            .ok()
+            .and_then(go_module_name);
+        let root = root_of(&dir);
+        packages.entry(root.clone()).or_insert_with(|| Package {
+            name: module.unwrap_or_else(|| directory_name(&dir)),
+            root,
+            kind: PackageKind::Go,
+        });
+    }
+
+    Ok(packages.into_values().collect())
+}
+
+/// マニフェストを読み込み、ディレクトリと解析した内容の対応表を返します
+///
+/// 解析できないマニフェストの内容は`None`とします。
+fn read_manifests(
+    repo: &Repository,
+    manifests: Vec<(String, Oid)>,
+    parse: impl Fn(&str) -> Option<Value>,
+) -> Result<BTreeMap<String, Option<Value>>, AnalyzerError> {
+    manifests
+        .into_iter()
+        .map(|(dir, id)| {
+            let blob = repo.find_blob(id)?;
+            let value = std::str::from_utf8(blob.content()).ok().and_then(&parse);
+            Ok((dir, value))
+        })
+        .collect()
+}
+
+/// ワークスペースを定義するマニフェストから、メンバーのパッケージを求めます
+///
+/// # 引数
+///
+/// - `manifests`: ディレクトリと解析したマニフェストの対応表
+/// - `kind`: マニフェストの種類
+fn workspace_members(
+    manifests: &BTreeMap<String, Option<Value>>,
+    kind: PackageKind,
+) -> Vec<Package> {
+    let mut members = Vec::new();
+    for (dir, manifest) in manifests {
+        let Some(manifest) = manifest else { continue };
+        let patterns = match kind {
+            PackageKind::Cargo => {
+                let Some(workspace) = manifest.get("workspace") else {
+                    continue;
+                };
+                // Cargoのワークスペースのルートは、それ自体もパッケージになれる
+                if manifest.get("package").is_some() {
+                    members.push(manifest_package(dir, Some(manifest), kind));
+                }
+                string_list(workspace.get("members"))
+                    .into_iter()
+                    .chain(
+                        string_list(workspace.get("exclude"))
+                            .into_iter()
+                            .map(|pattern| format!("!{}", pattern)),
+                    )
+                    .collect::<Vec<_>>()
+            }
+            PackageKind::Npm => match manifest.get("workspaces") {
+                Some(Value::Object(workspaces)) => string_list(workspaces.get("packages")),
+                workspaces => string_list(workspaces),
+            },
+            PackageKind::Go => continue,
+        };
+
+        let patterns: Vec<String> = patterns
+            .iter()
+            .map(|pattern| member_pattern(dir, pattern))
+            .collect();
+        let Ok(patterns) = GlobSet::new_exact(&patterns) else {
+            continue;
+        };
+        members.extend(
+            manifests
+                .iter()
+                .filter(|(member, _)| *member != dir && patterns.is_match(member))
+                .map(|(member, manifest)| manifest_package(member, manifest.as_ref(), kind)),
+        );
+    }
+    members
+}
+
+/// マニフェストの`name`（Cargoは`[package]`の`name`）からパッケージを作成します
+fn manifest_package(dir: &str, manifest: Option<&Value>, kind: PackageKind) -> Package {
+    let name = manifest
+        .and_then(|manifest| match kind {
+            PackageKind::Cargo => manifest.get("package")?.get("name"),
+            _ => manifest.get("name"),
+        })
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .unwrap_or_else(|| directory_name(dir));
+
+    Package {
+        name,
+        root: root_of(dir),
+        kind,
+    }
+}
+
+/// ワークスペースのメンバーのパターンを、リポジトリのルートに固定したパターンに変換します
+fn member_pattern(dir: &str, pattern: &str) -> String {
+    let (negated, pattern) = match pattern.strip_prefix('!') {
+        Some(pattern) => ("!", pattern),
+        None => ("", pattern),
+    };
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    if dir.is_empty() {
+        format!("{}/{}", negated, pattern)
+    } else {
+        format!("{}/{}/{}", negated, dir, pattern)
+    }
+}
+
+/// 文字列の配列の値を文字列のリストに変換します（配列でない値や文字列でない要素は無視します）
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// `go.mod`の`module`ディレクティブからモジュールパスを読み込みます
+fn go_module_name(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let line = line.split("//").next().unwrap_or_default().trim();
+        let module = line.strip_prefix("module")?;
+        if !module.starts_with(char::is_whitespace) {
+            return None;
+        }
+        let module = module.trim().trim_matches('"');
+        (!module.is_empty()).then(|| module.to_string())
+    })
+}
+
+/// ツリーの走査で得たディレクトリを、パッケージのルートディレクトリの表記に変換します
+fn root_of(dir: &str) -> String {
+    if dir.is_empty() {
+        ".".to_string()
+    } else {
+        dir.to_string()
+    }
+}
+
+/// ディレクトリ名を返します（リポジトリのルートは`.`）
+fn directory_name(dir: &str) -> String {
+    match dir.rsplit('/').next() {
+        Some(name) if !name.is_empty() => name.to_string(),
+        _ => ".".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::test_utils::commit_files;
+    use tempfile::TempDir;
+
+    fn package(name: &str, root: &str, kind: PackageKind) -> Package {
+        Package {
+            name: name.to_string(),
+            root: root.to_string(),
+            kind,
+        }
+    }
+
+    #[test]
+    fn test_discover_packages() -> Result<(), Box<dyn std::error::Error>> {
+        let temp_dir = TempDir::new()?;
+        let repo = Repository::init(temp_dir.path())?;
+        commit_files(
+            &repo,
+            "alice",
+            &[
+                (
+                    "Cargo.toml",
+                    Some(
+                        "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/legacy\"]\n\n\
+                         [package]\nname = \"app\"\n\n[[bin]]\nname = \"app-server\"\n",
+                    ),
+                ),
+                (
+                    "crates/core/Cargo.toml",
+                    Some("[package]\nname = \"app-core\"\n"),
+                ),
+                (
+                    "crates/core/examples/demo/Cargo.toml",
+                    Some("[package]\nname = \"demo\"\n"),
+                ),
+                (
+                    "crates/legacy/Cargo.toml",
+                    Some("[package]\nname = \"legacy\"\n"),
+                ),
+                (
+                    "crates/cli/Cargo.toml",
+                    Some(
+                        "[package]\nname = \"app-cli\"\ndescription = \"\"\"\nCommand line\n\"\"\"\n\n\
+                         [[bin]]\nname = \"app\"\n\n[[test]]\nname = \"e2e\"\n",
+                    ),
+                ),
+                (
+                    "web/package.json",
+                    Some(r#"{"private": true, "workspaces": {"packages": ["packages/*"]}}"#),
+                ),
+                (
+                    "web/packages/ui/package.json",
+                    Some(r#"{"name": "@acme/ui"}"#),
+                ),
+                (
+                    "web/packages/ui/test/fixture/package.json",
+                    Some(r#"{"name": "fixture"}"#),
+                ),
+                (
+                    "services/auth/go.mod",
+                    Some("// auth service\nmodule example.com/auth // main module\n\ngo 1.21\n"),
+                ),
+            ],
+            "Add workspace",
+        )?;
+
+        let tree = repo.head()?.peel_to_tree()?;
+        assert_eq!(
+            discover_packages(&repo, &tree)?,
+            vec![
+                package("app", ".", PackageKind::Cargo),
+                package("app-cli", "crates/cli", PackageKind::Cargo),
+                package("app-core", "crates/core", PackageKind::Cargo),
+                package("example.com/auth", "services/auth", PackageKind::Go),
+                package("@acme/ui", "web/packages/ui", PackageKind::Npm),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_package_of() {
+        let packages = vec![
+            package("app", ".", PackageKind::Cargo),
+            package("app-core", "crates/core", PackageKind::Cargo),
+            package("core-macros", "crates/core/macros", PackageKind::Cargo),
+        ];
+        let name_of = |path: &str| package_of(&packages, path).map(|p| p.name.as_str());
+
+        assert_eq!(name_of("src/main.rs"), Some("app"));
+        assert_eq!(name_of("crates/core/src/lib.rs"), Some("app-core"));
+        assert_eq!(
+            name_of("crates/core/macros/src/lib.rs"),
+            Some("core-macros")
+        );
+        assert_eq!(name_of("crates/core-utils/src/lib.rs"), Some("app"));
+        assert_eq!(package_of(&packages[1..], "src/main.rs"), None);
+    }
+
+    #[test]
+    fn test_go_module_name() {
+        assert_eq!(
+            go_module_name("module github.com/acme/api\n"),
+            Some("github.com/acme/api".to_string())
+        );
+        assert_eq!(
+            go_module_name("// comment\nmodule \"example.com/x\"\n"),
+            Some("example.com/x".to_string())
+        );
+        assert_eq!(go_module_name("modules foo\n"), None);
+        assert_eq!(go_module_name("go 1.21\n"), None);
+    }
+}
@@ -28,4 +28,10 @@
 //! ```
 
 pub mod analyzer;
-pub use analyzer::HotspotAnalyzer;
+pub use analyzer::{
+    detect_languages, language_patterns, parse_date, preset_patterns, AnalysisReport,
+    AnalyzerOptions, AuthorAliases, AuthorKey, ChangeSetKind, CoauthorCredit, CodeComplexity,
+    Component, CouplingOptions, Diagnostics, FileCoupling, GroupBy, GroupMetrics, HotspotAnalyzer,
+    LanguageMetrics, MergeMode, ProjectConfig, ScoreWeights, SkipReason, SkippedCommit,
+    DEFAULT_COAUTHOR_TRAILERS, DEFAULT_RENAME_THRESHOLD, DEFAULT_TICKET_PATTERN,
+};
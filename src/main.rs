@@ -1,7 +1,14 @@
 use anyhow::Context;
-use clap::Parser;
-use hotspot_analyzer::HotspotAnalyzer;
-use std::path::PathBuf;
+use chrono::Utc;
+use clap::{Args, Parser, Subcommand};
+use hotspot_analyzer::{
+    detect_languages, language_patterns, parse_date, preset_patterns, AnalyzerOptions,
+    AuthorAliases, AuthorKey, ChangeSetKind, CoauthorCredit, Component, CouplingOptions, GroupBy,
+    HotspotAnalyzer, MergeMode, ProjectConfig, ScoreWeights, DEFAULT_COAUTHOR_TRAILERS,
+    DEFAULT_RENAME_THRESHOLD, DEFAULT_TICKET_PATTERN,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 
 /// デフォルトのインクルードパターン
 const DEFAULT_INCLUDE_PATTERNS: &[&str] = &[
@@ -36,24 +43,74 @@ const DEFAULT_EXCLUDE_PATTERNS: &[&str] = &[
 #[command(
     version,
     about = "Analyzes Git repositories to identify code hotspots",
-    long_about = None
+    long_about = None,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
 )]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    analysis: AnalysisArgs,
+
+    #[command(flatten)]
+    report: ReportArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Report pairs of files that tend to change in the same commits (temporal coupling)
+    Coupling {
+        #[command(flatten)]
+        analysis: AnalysisArgs,
+
+        #[command(flatten)]
+        coupling: CouplingArgs,
+    },
+}
+
+/// ホットスポット分析と変更の結合度の分析に共通するオプション
+#[derive(Args)]
+struct AnalysisArgs {
     /// Path to Git repository
-    #[arg(short, long)]
-    repo: PathBuf,
+    // サブコマンドを指定した場合は最上位のオプションが省略されるため`Option`とし、
+    // 必須であることはclapで検査する
+    #[arg(short, long, required = true)]
+    repo: Option<PathBuf>,
 
-    /// Time window in days
-    #[arg(short = 'w', long = "time-window", default_value_t = 365)]
-    time_window: i64,
+    /// Configuration file (defaults to hotspot.toml at the repository root, if present)
+    #[arg(long, value_name = "FILE")]
+    config: Option<PathBuf>,
 
-    /// Output format (json or csv)
-    #[arg(short, long, default_value = "json")]
-    format: String,
+    /// Named profile from the configuration file ([profile.<NAME>])
+    #[arg(long, value_name = "NAME")]
+    profile: Option<String>,
 
-    /// Number of top hotspots to show
-    #[arg(short = 'n', long, default_value_t = 10)]
-    top: usize,
+    /// Time window in days [default: 365]
+    #[arg(short = 'w', long = "time-window")]
+    time_window: Option<i64>,
+
+    /// Analyze commits since this date (ISO 8601 or git-style relative date such as "2 weeks ago")
+    /// Overrides --time-window
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Analyze commits until this date (ISO 8601 or git-style relative date)
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Reference time used instead of the current time (ISO 8601), for reproducible reports
+    #[arg(long)]
+    as_of: Option<String>,
+
+    /// Output format (json or csv) [default: json]
+    #[arg(short, long)]
+    format: Option<String>,
+
+    /// Number of top hotspots (or file pairs for coupling) to show [default: 10]
+    #[arg(short = 'n', long)]
+    top: Option<usize>,
 
     /// Include only files matching these patterns (glob format, e.g., "*.rs", "src/**/*.py")
     /// If not specified, default includes common source code files
@@ -66,82 +123,686 @@ struct Cli {
     exclude_patterns: Option<Vec<String>>,
 
     /// Use no default include patterns
-    #[arg(long)]
+    #[arg(long, overrides_with = "default_includes")]
     no_default_includes: bool,
 
+    /// Use the default include patterns even if the configuration file sets no_default_includes
+    #[arg(long, overrides_with = "no_default_includes")]
+    default_includes: bool,
+
+    /// Use the include patterns of a language preset (jvm, web, mobile, dotnet, systems, scripting, all)
+    /// or of a single language (e.g. "kotlin") instead of the default include patterns
+    #[arg(long = "preset", value_name = "NAME")]
+    presets: Vec<String>,
+
+    /// Choose include patterns from the languages found in the HEAD tree instead of the default include patterns
+    #[arg(long)]
+    auto_detect: bool,
+
     /// Use no default exclude patterns
     #[arg(long)]
     no_default_excludes: bool,
 
-    /// Include merge commits in the analysis
-    #[arg(long, default_value_t = false)]
+    /// Also exclude files matching the .gitignore files committed at the analyzed revision
+    #[arg(long)]
+    respect_gitignore: bool,
+
+    /// Do not exclude files marked linguist-generated, linguist-vendored or -diff in .gitattributes
+    #[arg(long)]
+    no_gitattributes: bool,
+
+    /// Do not exclude files listed in the .hotspotignore files committed at the analyzed revision
+    #[arg(long)]
+    no_hotspotignore: bool,
+
+    /// Include merge commits in the analysis (same as --merge-mode first-parent)
+    #[arg(long, default_value_t = false, conflicts_with = "merge_mode")]
     include_merges: bool,
+
+    /// How merge commits are diffed: "skip", "first-parent", "all-parents" (files differing from any parent)
+    /// or "combined" (files differing from every parent, i.e. conflict resolutions)
+    #[arg(long, default_value = "skip")]
+    merge_mode: MergeMode,
+
+    /// Follow only the first parent of merge commits when walking the history
+    #[arg(long)]
+    first_parent: bool,
+
+    /// Similarity threshold (percent) for rename and copy detection
+    #[arg(
+        long,
+        default_value_t = DEFAULT_RENAME_THRESHOLD,
+        value_parser = clap::value_parser!(u16).range(0..=100)
+    )]
+    rename_threshold: u16,
+
+    /// Disable rename and copy detection
+    #[arg(long)]
+    no_renames: bool,
+
+    /// Also detect files moved into or out of the analyzed paths and include patterns
+    /// (diffs the whole tree for commits that add or delete analyzed files)
+    #[arg(long, conflicts_with = "no_renames")]
+    renames_across_scope: bool,
+
+    /// Do not attribute the history of renamed files to their current path
+    #[arg(long)]
+    no_follow_renames: bool,
+
+    /// Also report files that no longer exist at the end of the analysis period, with the time they were deleted
+    #[arg(long)]
+    include_deleted: bool,
+
+    /// Also analyze binary files (skipped by default)
+    #[arg(long)]
+    include_binary: bool,
+
+    /// Skip files whose header marks them as generated ("Code generated ... DO NOT EDIT", "@generated", ...)
+    #[arg(long)]
+    skip_generated: bool,
+
+    /// Do not normalize author identities with the repository's .mailmap
+    #[arg(long)]
+    no_mailmap: bool,
+
+    /// Identify authors by "name" or "email"
+    #[arg(long, default_value = "name")]
+    author_key: AuthorKey,
+
+    /// File mapping author aliases to canonical names ("Canonical Name = alias1, alias2")
+    #[arg(long)]
+    alias_file: Option<PathBuf>,
+
+    /// Additional commit message trailers that name co-authors (e.g. "Paired-with")
+    #[arg(long = "coauthor-trailer")]
+    coauthor_trailers: Vec<String>,
+
+    /// Do not credit co-authors named in commit message trailers
+    #[arg(long)]
+    no_coauthors: bool,
+
+    /// Credit given to each co-author: "full" (one commit each) or "fractional" (shared)
+    #[arg(long, default_value = "full")]
+    coauthor_credit: CoauthorCredit,
+
+    /// Analyze only commits by these authors (exact name or email, or /regex/)
+    #[arg(long = "include-author", value_name = "AUTHOR")]
+    include_authors: Vec<String>,
+
+    /// Skip commits by these authors (exact name or email, or /regex/)
+    #[arg(long = "exclude-author", value_name = "AUTHOR")]
+    exclude_authors: Vec<String>,
+
+    /// Do not exclude well-known bots and service accounts (dependabot, renovate, *[bot], ...)
+    #[arg(long)]
+    include_bots: bool,
+
+    /// Revisions or ranges to analyze in git revision syntax (e.g. "v1.3.0", "v1.2.0..v1.3.0")
+    /// If not specified, analyzes the history of HEAD
+    #[arg(long = "rev")]
+    revisions: Vec<String>,
+
+    /// Analyze the history of every local branch
+    #[arg(long = "all")]
+    all_branches: bool,
+
+    /// Limit the analysis to these directories or files (relative to the repository root)
+    #[arg(short = 'p', long = "path")]
+    paths: Vec<String>,
+
+    /// Skip commits that change more than this many analyzed files (e.g. mass reformatting)
+    #[arg(long, value_name = "N")]
+    max_files_per_commit: Option<usize>,
+
+    /// Skip commits whose message matches this regular expression (e.g. "^chore\(fmt\)")
+    #[arg(long = "exclude-message", value_name = "REGEX")]
+    exclude_messages: Vec<String>,
+
+    /// Number of threads used to diff commits (0 uses all available CPUs)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    jobs: usize,
+
+    /// Cache per-commit diff results in .git/hotspot-cache so later runs only diff new commits
+    #[arg(long)]
+    cache: bool,
+
+    /// Directory used for the per-commit cache (implies --cache)
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
 }
 
-impl Cli {
-    fn get_include_patterns(&self) -> Vec<String> {
+/// ホットスポットの出力に関するオプション
+#[derive(Args)]
+struct ReportArgs {
+    /// Weight of line churn (lines added + deleted) in the hotspot score (0 disables it) [default: 0]
+    #[arg(long)]
+    churn_weight: Option<f64>,
+
+    /// Report sum_of_coupling: how many other files were changed in the same commits, summed over commits
+    /// (commits changing more than --sum-of-coupling-max-files analyzed files and deleted files are not counted)
+    #[arg(long)]
+    sum_of_coupling: bool,
+
+    /// Ignore commits that change more than this many analyzed files in sum_of_coupling (0 disables the limit)
+    #[arg(
+        long = "sum-of-coupling-max-files",
+        value_name = "N",
+        default_value_t = 30
+    )]
+    sum_of_coupling_max_changeset_size: usize,
+
+    /// Weight of sum_of_coupling in the hotspot score (0 disables it, implies --sum-of-coupling) [default: 0]
+    #[arg(long)]
+    sum_of_coupling_weight: Option<f64>,
+
+    /// Report lines of code and complexity read from each file at the analyzed revision
+    /// (indentation-based; cyclomatic complexity too when built with the tree-sitter feature)
+    #[arg(long)]
+    complexity: bool,
+
+    /// Weight of lines of code in the hotspot score (0 disables it, implies --complexity) [default: 0]
+    #[arg(long)]
+    loc_weight: Option<f64>,
+
+    /// Weight of indentation-based complexity in the hotspot score (0 disables it, implies --complexity) [default: 0]
+    #[arg(long)]
+    complexity_weight: Option<f64>,
+
+    /// Weight of cyclomatic complexity in the hotspot score (0 disables it, implies --complexity) [default: 0]
+    #[arg(long)]
+    cyclomatic_weight: Option<f64>,
+
+    /// Write the commits skipped by --max-files-per-commit and --exclude-message, with reasons, to this file as JSON
+    #[arg(long, value_name = "FILE")]
+    skipped_report: Option<PathBuf>,
+
+    /// Report hotspots per group instead of per file: "dir:N" (first N directory levels), "component" or "package"
+    #[arg(long, value_name = "dir:N|component|package")]
+    group_by: Option<GroupBy>,
+
+    /// Define a component for --group-by component (e.g. "api=services/api/**,proto/api/**")
+    #[arg(long = "component", value_name = "NAME=PATTERN,...")]
+    components: Vec<Component>,
+
+    /// Tag each file with its workspace package (Cargo workspace members, npm workspaces, Go modules)
+    #[arg(long)]
+    packages: bool,
+
+    /// Report only files in these workspace packages (implies --packages)
+    #[arg(long = "package", value_name = "NAME")]
+    package_filter: Vec<String>,
+
+    /// Write per-language aggregates to this file as JSON
+    #[arg(long, value_name = "FILE")]
+    language_report: Option<PathBuf>,
+}
+
+/// 変更の結合度の分析のオプション
+#[derive(Args)]
+struct CouplingArgs {
+    /// Ignore files changed in fewer commits than this
+    #[arg(long, default_value_t = 5)]
+    min_revisions: u32,
+
+    /// Report only pairs changed together in at least this many commits
+    #[arg(long, default_value_t = 2)]
+    min_shared_commits: u32,
+
+    /// Report only pairs whose coupling degree (shared commits / average revisions) is at least this (0.0-1.0)
+    #[arg(long, default_value_t = 0.3)]
+    min_degree: f64,
+
+    /// Ignore change sets that change more than this many analyzed files (0 disables the limit)
+    #[arg(long, default_value_t = 30)]
+    max_changeset_size: usize,
+
+    /// Treat files as changed together within a "commit", commits referencing the same "ticket", commits by the same author at most N minutes apart ("author:N"), commits brought in by the same "merge", or any commits at most N minutes apart ("window:N")
+    #[arg(
+        long,
+        default_value = "commit",
+        value_name = "commit|ticket|author:N|merge|window:N"
+    )]
+    change_set: ChangeSetKind,
+
+    /// Regular expression extracting the ticket ID from commit messages for --change-set ticket
+    #[arg(long, default_value = DEFAULT_TICKET_PATTERN)]
+    ticket_pattern: String,
+
+    /// Also analyze this repository and report only pairs of files in different repositories
+    /// (NAME defaults to the directory name; link commits with --change-set ticket or window:N).
+    /// --rev and --path apply to the --repo repository only; with --cache-dir, each repository is cached in its own NAME subdirectory.
+    /// window:N chains commits transitively, so a change set may span more than N minutes while commits keep arriving
+    #[arg(long = "with-repo", value_name = "[NAME=]PATH")]
+    other_repos: Vec<String>,
+}
+
+impl AnalysisArgs {
+    /// 分析対象のGitリポジトリのパスを返します
+    fn repo(&self) -> &Path {
+        self.repo.as_deref().expect("--repo is required")
+    }
+
+    /// 設定ファイルを読み込み、指定されたプロファイルを適用します
+    ///
+    /// `--config`が指定されていない場合は、リポジトリのルートの`hotspot.toml`を使用します。
+    fn load_config(&self) -> anyhow::Result<ProjectConfig> {
+        let config = match self.config {
+            Some(ref path) => Some(ProjectConfig::from_file(path)?),
+            None => ProjectConfig::discover(self.repo())?,
+        };
+
+        match (config, &self.profile) {
+            (Some(config), Some(profile)) => Ok(config.with_profile(profile)?),
+            (Some(config), None) => Ok(config),
+            (None, Some(profile)) => {
+                anyhow::bail!("Profile {} requires a configuration file", profile)
+            }
+            (None, None) => Ok(ProjectConfig::default()),
+        }
+    }
+
+    /// インクルードパターンを返します
+    ///
+    /// プリセットや言語の自動検出が指定されている場合は、デフォルトのパターンの代わりに
+    /// それらのパターンを使用します。
+    /// コマンドラインで指定したパターンは、設定ファイルのパターンを置き換えます。
+    fn get_include_patterns(&self, config: &ProjectConfig) -> anyhow::Result<Vec<String>> {
         let mut patterns = Vec::new();
 
-        if !self.no_default_includes {
+        let presets = if self.presets.is_empty() {
+            config.preset.clone().unwrap_or_default()
+        } else {
+            self.presets.clone()
+        };
+        for preset in &presets {
+            patterns.extend(preset_patterns(preset)?);
+        }
+
+        if self.auto_detect || config.auto_detect == Some(true) {
+            let detected = detect_languages(self.repo()).context("Failed to detect languages")?;
+            if detected.is_empty() {
+                eprintln!("No known languages detected; using the default include patterns");
+            } else {
+                let summary: Vec<String> = detected
+                    .iter()
+                    .map(|language| format!("{} ({} file(s))", language.language, language.files))
+                    .collect();
+                eprintln!("Detected languages: {}", summary.join(", "));
+                let languages: Vec<&str> =
+                    detected.iter().map(|language| language.language).collect();
+                patterns.extend(language_patterns(&languages));
+            }
+        }
+
+        // コマンドラインの指定を設定ファイルより優先する
+        let no_default_includes = if self.default_includes {
+            false
+        } else {
+            self.no_default_includes || config.no_default_includes == Some(true)
+        };
+        if patterns.is_empty() && !no_default_includes {
             patterns.extend(DEFAULT_INCLUDE_PATTERNS.iter().map(|s| s.to_string()));
         }
+        // 複数のプリセットに含まれる言語のパターンは1つにまとめる
+        let mut seen = std::collections::HashSet::new();
+        patterns.retain(|pattern| seen.insert(pattern.clone()));
 
-        if let Some(ref user_patterns) = self.include_patterns {
+        if let Some(user_patterns) = self.include_patterns.as_ref().or(config.include.as_ref()) {
             patterns.extend(user_patterns.clone());
         }
 
-        patterns
+        Ok(patterns)
     }
 
-    fn get_exclude_patterns(&self) -> Vec<String> {
+    /// 除外パターンを返します
+    ///
+    /// コマンドラインで指定したパターンは、設定ファイルのパターンを置き換えます。
+    fn get_exclude_patterns(&self, config: &ProjectConfig) -> Vec<String> {
         let mut patterns = Vec::new();
 
-        if !self.no_default_excludes {
+        if !(self.no_default_excludes || config.no_default_excludes == Some(true)) {
             patterns.extend(DEFAULT_EXCLUDE_PATTERNS.iter().map(|s| s.to_string()));
         }
 
-        if let Some(ref user_patterns) = self.exclude_patterns {
+        if let Some(user_patterns) = self.exclude_patterns.as_ref().or(config.exclude.as_ref()) {
             patterns.extend(user_patterns.clone());
         }
 
         patterns
     }
+
+    fn get_analyzer_options(&self, config: &ProjectConfig) -> anyhow::Result<AnalyzerOptions> {
+        // 別名ファイルの定義は設定ファイルの定義より優先する
+        let mut author_aliases = config.author_aliases();
+        if let Some(ref path) = self.alias_file {
+            author_aliases
+                .merge(AuthorAliases::from_file(path).context("Failed to load alias file")?);
+        }
+
+        let coauthor_trailers = if self.no_coauthors {
+            Vec::new()
+        } else {
+            DEFAULT_COAUTHOR_TRAILERS
+                .iter()
+                .map(|s| s.to_string())
+                .chain(self.coauthor_trailers.iter().cloned())
+                .collect()
+        };
+
+        let as_of = self
+            .as_of
+            .as_deref()
+            .map(|date| parse_date(date, Utc::now()))
+            .transpose()
+            .context("Invalid --as-of date")?;
+        // 相対日時は基準日時からの相対として解釈する
+        let reference = as_of.unwrap_or_else(Utc::now);
+        let since = self
+            .since
+            .as_deref()
+            .map(|date| parse_date(date, reference))
+            .transpose()
+            .context("Invalid --since date")?;
+        let until = self
+            .until
+            .as_deref()
+            .map(|date| parse_date(date, reference))
+            .transpose()
+            .context("Invalid --until date")?;
+
+        Ok(AnalyzerOptions {
+            time_window_days: self.time_window.or(config.time_window).unwrap_or(365),
+            since,
+            until,
+            as_of,
+            include_patterns: self.get_include_patterns(config)?,
+            exclude_patterns: self.get_exclude_patterns(config),
+            use_gitignore: self.respect_gitignore,
+            use_gitattributes: !self.no_gitattributes,
+            use_hotspotignore: !self.no_hotspotignore,
+            merge_mode: if self.include_merges {
+                MergeMode::FirstParent
+            } else {
+                self.merge_mode
+            },
+            first_parent: self.first_parent,
+            rename_threshold: (!self.no_renames).then_some(self.rename_threshold),
+            renames_across_scope: self.renames_across_scope,
+            skip_binary: !self.include_binary,
+            skip_generated: self.skip_generated,
+            follow_renames: !self.no_follow_renames,
+            include_deleted: self.include_deleted,
+            score_weights: config.score_weights(ScoreWeights::default()),
+            sum_of_coupling: false,
+            sum_of_coupling_max_changeset_size: Some(30),
+            complexity: false,
+            group_by: None,
+            components: Vec::new(),
+            detect_packages: false,
+            use_mailmap: !self.no_mailmap,
+            author_key: self.author_key,
+            author_aliases,
+            coauthor_trailers,
+            coauthor_credit: self.coauthor_credit,
+            include_authors: self.include_authors.clone(),
+            exclude_authors: self.exclude_authors.clone(),
+            exclude_bots: !self.include_bots,
+            revisions: self.revisions.clone(),
+            all_branches: self.all_branches,
+            paths: self.paths.clone(),
+            jobs: self.jobs,
+            cache: self.cache,
+            cache_dir: self.cache_dir.clone(),
+            max_files_per_commit: self.max_files_per_commit,
+            exclude_messages: self.exclude_messages.clone(),
+        })
+    }
+
+    /// 出力形式と出力する件数を返します
+    fn output_settings(&self, config: &ProjectConfig) -> (String, usize) {
+        let format = self
+            .format
+            .clone()
+            .or_else(|| config.format.clone())
+            .unwrap_or_else(|| "json".to_string());
+        (format, self.top.or(config.top).unwrap_or(10))
+    }
+}
+
+impl ReportArgs {
+    /// スコアの重みやグループ単位の集計など、ホットスポットの出力に関するオプションを反映します
+    fn apply(&self, config: &ProjectConfig, options: &mut AnalyzerOptions) -> anyhow::Result<()> {
+        if let Some(churn) = self.churn_weight {
+            options.score_weights.churn = churn;
+        }
+        if let Some(weight) = self.sum_of_coupling_weight {
+            options.score_weights.sum_of_coupling = weight;
+        }
+        options.sum_of_coupling = self.sum_of_coupling;
+        options.sum_of_coupling_max_changeset_size = (self.sum_of_coupling_max_changeset_size > 0)
+            .then_some(self.sum_of_coupling_max_changeset_size);
+        if let Some(weight) = self.loc_weight {
+            options.score_weights.lines_of_code = weight;
+        }
+        if let Some(weight) = self.complexity_weight {
+            options.score_weights.complexity = weight;
+        }
+        if let Some(weight) = self.cyclomatic_weight {
+            options.score_weights.cyclomatic_complexity = weight;
+        }
+        options.complexity = self.complexity;
+
+        options.group_by = match (self.group_by, &config.group_by) {
+            (Some(group_by), _) => Some(group_by),
+            (None, Some(group_by)) => Some(
+                group_by
+                    .parse()
+                    .context("Invalid group_by in configuration file")?,
+            ),
+            (None, None) => None,
+        };
+        // コマンドラインで定義したコンポーネントは、設定ファイルの同名のコンポーネントより優先する
+        options.components = config
+            .components()
+            .into_iter()
+            .filter(|component| self.components.iter().all(|c| c.name != component.name))
+            .collect();
+        options.components.extend(self.components.iter().cloned());
+        options.detect_packages = self.packages || !self.package_filter.is_empty();
+
+        Ok(())
+    }
+}
+
+impl CouplingArgs {
+    fn coupling_options(&self) -> CouplingOptions {
+        CouplingOptions {
+            min_revisions: self.min_revisions,
+            min_shared_commits: self.min_shared_commits,
+            min_degree: self.min_degree,
+            max_changeset_size: (self.max_changeset_size > 0).then_some(self.max_changeset_size),
+            change_set: self.change_set,
+            ticket_pattern: self.ticket_pattern.clone(),
+        }
+    }
+
+    /// `--with-repo`で指定されたリポジトリの名前とパスを返します
+    fn other_repos(&self) -> Vec<(String, PathBuf)> {
+        self.other_repos
+            .iter()
+            .map(|value| match value.split_once('=') {
+                Some((name, path)) => (name.to_string(), PathBuf::from(path)),
+                None => (repository_name(Path::new(value)), PathBuf::from(value)),
+            })
+            .collect()
+    }
+}
+
+/// リポジトリのディレクトリ名を、リポジトリを識別する名前として返します
+fn repository_name(path: &Path) -> String {
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// `--with-repo`で追加したリポジトリの分析オプションを返します
+///
+/// `--rev`と`--path`は`--repo`のリポジトリのリビジョンとパスを指すため適用しません。
+/// `--cache-dir`が指定されている場合は、リポジトリの名前のサブディレクトリをキャッシュに使用します。
+fn other_repo_options(options: &AnalyzerOptions, name: &str) -> AnalyzerOptions {
+    AnalyzerOptions {
+        revisions: Vec::new(),
+        paths: Vec::new(),
+        cache_dir: options.cache_dir.as_ref().map(|dir| dir.join(name)),
+        ..options.clone()
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    match &cli.command {
+        Some(Command::Coupling { analysis, coupling }) => run_coupling(analysis, coupling),
+        None => run_hotspots(&cli.analysis, &cli.report),
+    }
+}
+
+/// 変更の結合度を分析し、ファイルの組を出力します
+///
+/// `--with-repo`が指定されている場合は、リポジトリをまたぐファイルの組を出力します。
+/// 追加したリポジトリの分析オプションは`other_repo_options`で決めます。
+fn run_coupling(args: &AnalysisArgs, coupling: &CouplingArgs) -> anyhow::Result<()> {
+    let config = args
+        .load_config()
+        .context("Failed to load configuration file")?;
+    let (format, top) = args.output_settings(&config);
+
+    let options = args.get_analyzer_options(&config)?;
+    let analyzer = HotspotAnalyzer::with_options(args.repo(), options.clone())
+        .context("Failed to initialize analyzer")?;
+    let mut couplings = if coupling.other_repos.is_empty() {
+        analyzer
+            .analyze_coupling(&coupling.coupling_options())
+            .context("Failed to analyze coupling")?
+    } else {
+        let mut repositories = vec![(repository_name(args.repo()), analyzer)];
+        for (name, path) in coupling.other_repos() {
+            let options = other_repo_options(&options, &name);
+            let analyzer = HotspotAnalyzer::with_options(&path, options)
+                .with_context(|| format!("Failed to initialize analyzer for {}", path.display()))?;
+            repositories.push((name, analyzer));
+        }
+        HotspotAnalyzer::analyze_cross_repository_coupling(
+            &repositories,
+            &coupling.coupling_options(),
+        )
+        .context("Failed to analyze cross-repository coupling")?
+    };
+    couplings.truncate(top);
 
-    let analyzer = HotspotAnalyzer::new(
-        &cli.repo,
-        cli.time_window,
-        cli.get_include_patterns(),
-        cli.get_exclude_patterns(),
-        cli.include_merges,
-    )
-    .context("Failed to initialize analyzer")?;
+    print_records(&couplings, &format)
+}
+
+/// ホットスポットを分析し、ファイルまたはグループごとの結果を出力します
+fn run_hotspots(args: &AnalysisArgs, report_args: &ReportArgs) -> anyhow::Result<()> {
+    let config = args
+        .load_config()
+        .context("Failed to load configuration file")?;
+    let (format, top) = args.output_settings(&config);
+
+    let mut options = args.get_analyzer_options(&config)?;
+    report_args.apply(&config, &mut options)?;
+    let grouped = options.group_by.is_some();
+    let analyzer = HotspotAnalyzer::with_options(args.repo(), options)
+        .context("Failed to initialize analyzer")?;
+
+    let report = analyzer
+        .analyze_report()
+        .context("Failed to analyze repository")?;
+
+    if !report.skipped_commits.is_empty() {
+        eprintln!(
+            "Skipped {} commit(s) by commit filters",
+            report.skipped_commits.len()
+        );
+    }
+    if let Some(path) = &report_args.language_report {
+        let json = serde_json::to_string_pretty(&report.languages)
+            .context("Failed to serialize language aggregates")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    if let Some(path) = &report_args.skipped_report {
+        let json = serde_json::to_string_pretty(&report.skipped_commits)
+            .context("Failed to serialize skipped commits")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    let diagnostics = &report.diagnostics;
+    if diagnostics.binary_files > 0 || diagnostics.generated_files > 0 {
+        eprintln!(
+            "Skipped {} binary file(s) and {} generated file(s)",
+            diagnostics.binary_files, diagnostics.generated_files
+        );
+    }
 
-    let mut hotspots = analyzer.analyze().context("Failed to analyze repository")?;
+    // グループ単位で集計した場合は、ファイルの代わりにグループのホットスポットを出力する
+    if grouped {
+        let mut groups = report.groups;
+        groups.sort_by(|a, b| b.hotspot_score.partial_cmp(&a.hotspot_score).unwrap());
+        groups.truncate(top);
+        return print_records(&groups, &format);
+    }
+
+    for name in &report_args.package_filter {
+        if !report.packages.iter().any(|package| &package.name == name) {
+            let detected: Vec<_> = report.packages.iter().map(|p| p.name.as_str()).collect();
+            if detected.is_empty() {
+                anyhow::bail!("Unknown package: {} (no packages were detected)", name);
+            }
+            anyhow::bail!(
+                "Unknown package: {} (detected packages: {})",
+                name,
+                detected.join(", ")
+            );
+        }
+    }
+
+    let mut hotspots = report.files;
+    if !report_args.package_filter.is_empty() {
+        hotspots.retain(|metric| {
+            metric
+                .package
+                .as_ref()
+                .is_some_and(|package| report_args.package_filter.contains(package))
+        });
+    }
 
     hotspots.sort_by(|a, b| b.hotspot_score.partial_cmp(&a.hotspot_score).unwrap());
-    let top_hotspots: Vec<_> = hotspots.into_iter().take(cli.top).collect();
+    let top_hotspots: Vec<_> = hotspots.into_iter().take(top).collect();
+
+    print_records(&top_hotspots, &format)
+}
 
-    match cli.format.as_str() {
+/// 分析結果を指定された形式で標準出力に出力します
+fn print_records<T: Serialize>(records: &[T], format: &str) -> anyhow::Result<()> {
+    match format {
         "json" => {
             println!(
                 "{}",
-                serde_json::to_string_pretty(&top_hotspots)
-                    .context("Failed to serialize to JSON")?
+                serde_json::to_string_pretty(records).context("Failed to serialize to JSON")?
             );
         }
         "csv" => {
             let mut wtr = csv::Writer::from_writer(std::io::stdout());
-            for metric in top_hotspots {
-                wtr.serialize(metric)
+            for record in records {
+                wtr.serialize(record)
                     .context("Failed to write CSV record")?;
             }
             wtr.flush().context("Failed to flush CSV writer")?;
         }
-        _ => anyhow::bail!("Unsupported output format: {}", cli.format),
+        _ => anyhow::bail!("Unsupported output format: {}", format),
     }
 
     Ok(())